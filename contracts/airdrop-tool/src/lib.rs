@@ -0,0 +1,207 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use rusd_indexer::{Indexer, RpcEvent};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    Int128Parts, Limits, ScAddress, ScMap, ScMapEntry, ScSymbol, ScVal, WriteXdr,
+};
+
+pub type Hash = [u8; 32];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    Indexer(rusd_indexer::Error),
+    InvalidAccount(String),
+    NoHolders,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Indexer(err) => write!(f, "{err}"),
+            Error::InvalidAccount(account) => write!(f, "invalid account {account}"),
+            Error::NoHolders => write!(f, "no holders at the snapshot ledger"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<rusd_indexer::Error> for Error {
+    fn from(err: rusd_indexer::Error) -> Self {
+        Error::Indexer(err)
+    }
+}
+
+// Hash of the airdrop contract's `Leaf { account, index, shares }`, i.e.
+// sha256 of its XDR encoding as a contracttype map.
+pub fn leaf_hash(account: &str, index: u32, shares: i128) -> Result<Hash, Error> {
+    let address =
+        ScAddress::from_str(account).map_err(|_| Error::InvalidAccount(account.to_string()))?;
+    let entries = vec![
+        field("account", ScVal::Address(address)),
+        field("index", ScVal::U32(index)),
+        field(
+            "shares",
+            ScVal::I128(Int128Parts {
+                hi: (shares >> 64) as i64,
+                lo: shares as u64,
+            }),
+        ),
+    ];
+    let leaf = ScVal::Map(Some(ScMap(entries.try_into().unwrap())));
+    Ok(Sha256::digest(leaf.to_xdr(Limits::none()).unwrap()).into())
+}
+
+fn field(name: &str, val: ScVal) -> ScMapEntry {
+    ScMapEntry {
+        key: ScVal::Symbol(ScSymbol(name.try_into().unwrap())),
+        val,
+    }
+}
+
+fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    let (low, high) = if a > b { (b, a) } else { (a, b) };
+    let mut hasher = Sha256::new();
+    hasher.update(low);
+    hasher.update(high);
+    hasher.finalize().into()
+}
+
+// Tree with sorted pair hashing, as `Verifier::verify` expects. An odd node
+// at the end of a layer moves up unchanged.
+pub struct MerkleTree {
+    layers: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        MerkleTree { layers }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.layers.last().unwrap()[0]
+    }
+
+    pub fn proof(&self, mut position: usize) -> Vec<Hash> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(position ^ 1) {
+                proof.push(*sibling);
+            }
+            position /= 2;
+        }
+        proof
+    }
+}
+
+pub fn verify(proof: &[Hash], root: &Hash, leaf: Hash) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling))
+        == *root
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Claim {
+    pub index: u32,
+    pub account: String,
+    pub shares: i128,
+    pub proof: Vec<String>,
+}
+
+// Everything `create_distribution` and `claim` need, as written to JSON.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AirdropTree {
+    pub root: String,
+    pub snapshot_ledger: u32,
+    pub total_shares: i128,
+    pub claims: Vec<Claim>,
+}
+
+impl AirdropTree {
+    // One leaf per holder with shares, indexed in account order.
+    pub fn from_shares(
+        snapshot_ledger: u32,
+        holders: &BTreeMap<String, i128>,
+    ) -> Result<Self, Error> {
+        let holders: Vec<_> = holders.iter().filter(|(_, shares)| **shares > 0).collect();
+        if holders.is_empty() {
+            return Err(Error::NoHolders);
+        }
+        let leaves = holders
+            .iter()
+            .enumerate()
+            .map(|(index, (account, shares))| leaf_hash(account, index as u32, **shares))
+            .collect::<Result<Vec<_>, _>>()?;
+        let tree = MerkleTree::new(leaves);
+        let claims = holders
+            .iter()
+            .enumerate()
+            .map(|(index, (account, shares))| Claim {
+                index: index as u32,
+                account: (*account).clone(),
+                shares: **shares,
+                proof: tree.proof(index).iter().map(to_hex).collect(),
+            })
+            .collect();
+        Ok(AirdropTree {
+            root: to_hex(&tree.root()),
+            snapshot_ledger,
+            total_shares: holders.iter().map(|(_, shares)| **shares).sum(),
+            claims,
+        })
+    }
+
+    // Share balances of `contract_id` at `ledger`, rebuilt from exported
+    // events. Queued redemptions (held under the token's own address) and
+    // `exclude` get no leaf.
+    pub fn from_events(
+        events: &[RpcEvent],
+        contract_id: &str,
+        ledger: u32,
+        exclude: &[String],
+    ) -> Result<Self, Error> {
+        let mut indexer = Indexer::new(contract_id);
+        indexer.ingest(events)?;
+        let mut holders = indexer.snapshot(ledger)?.shares;
+        holders.remove(contract_id);
+        for account in exclude {
+            holders.remove(account);
+        }
+        Self::from_shares(ledger, &holders)
+    }
+}
+
+pub fn to_hex(hash: &Hash) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn from_hex(hex: &str) -> Option<Hash> {
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut hash = [0; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(2 * i..2 * i + 2)?, 16).ok()?;
+    }
+    Some(hash)
+}
+
+#[cfg(test)]
+mod test;
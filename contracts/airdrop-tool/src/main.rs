@@ -0,0 +1,60 @@
+use std::{fs, path::PathBuf};
+
+use clap::Parser;
+use rusd_airdrop_tool::AirdropTree;
+use rusd_indexer::load_fixture;
+
+// Builds the Merkle tree and proofs for an airdrop from an export of rUSD
+// events (a saved `getEvents` response or its event list).
+#[derive(Parser)]
+#[command(name = "rusd-airdrop-tool")]
+struct Args {
+    /// JSON export of the token's events
+    #[arg(long)]
+    events: PathBuf,
+    /// rUSD contract id
+    #[arg(long)]
+    contract: String,
+    /// Snapshot ledger; balances include every event up to it
+    #[arg(long)]
+    ledger: u32,
+    /// Accounts left out of the tree (pools, treasury, ...)
+    #[arg(long)]
+    exclude: Vec<String>,
+    /// Output file; prints to stdout when omitted
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let result = load_fixture(&args.events)
+        .map_err(|err| err.to_string())
+        .and_then(|events| {
+            AirdropTree::from_events(&events, &args.contract, args.ledger, &args.exclude)
+                .map_err(|err| err.to_string())
+        });
+    let tree = match result {
+        Ok(tree) => tree,
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+    };
+    let json = serde_json::to_string_pretty(&tree).unwrap();
+    match args.out {
+        Some(path) => {
+            fs::write(&path, json).unwrap_or_else(|err| {
+                eprintln!("error: cannot write {}: {err}", path.display());
+                std::process::exit(1);
+            });
+            eprintln!(
+                "root {} over {} holders, {} shares",
+                tree.root,
+                tree.claims.len(),
+                tree.total_shares
+            );
+        }
+        None => println!("{json}"),
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use rusd_airdrop::{Error as AirdropError, RUsdAirdrop, RUsdAirdropClient};
+use rusd_indexer::{load_fixture, RpcEvent};
+use rusd_rebasing_token::testutils::Scenario;
+use soroban_sdk::{
+    testutils::{Address as _, Events as _},
+    xdr::{ScVal, WriteXdr},
+    Address, BytesN, Symbol, TryFromVal, Val, Vec as SorobanVec,
+};
+use stellar_xdr::curr::{ContractId, Hash as XdrHash, Limits, ScAddress};
+
+use crate::{from_hex, leaf_hash, verify, AirdropTree, Error};
+
+// Deterministic xorshift, so generated holder sets are reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn generated_holders(rng: &mut Rng, count: usize) -> BTreeMap<String, i128> {
+    (0..count)
+        .map(|_| {
+            let mut id = [0u8; 32];
+            for chunk in id.chunks_mut(8) {
+                chunk.copy_from_slice(&rng.next().to_be_bytes());
+            }
+            let account = ScAddress::Contract(ContractId(XdrHash(id))).to_string();
+            let shares = (rng.next() % 1_000_000_000) as i128 + 1;
+            (account, shares)
+        })
+        .collect()
+}
+
+#[test]
+fn generated_holder_sets_produce_verifiable_proofs() {
+    let mut rng = Rng(0x5eed);
+    for count in [1, 2, 3, 5, 8, 33, 100, 257] {
+        let holders = generated_holders(&mut rng, count);
+        let tree = AirdropTree::from_shares(42, &holders).unwrap();
+        let root = from_hex(&tree.root).unwrap();
+        assert_eq!(tree.claims.len(), count);
+        assert_eq!(tree.total_shares, holders.values().sum::<i128>());
+        assert_eq!(AirdropTree::from_shares(42, &holders).unwrap(), tree);
+
+        for claim in &tree.claims {
+            assert_eq!(holders[&claim.account], claim.shares);
+            let proof: Vec<_> = claim
+                .proof
+                .iter()
+                .map(|hex| from_hex(hex).unwrap())
+                .collect();
+            assert!(proof.len() <= 9);
+            let leaf = leaf_hash(&claim.account, claim.index, claim.shares).unwrap();
+            assert!(verify(&proof, &root, leaf));
+            let inflated = leaf_hash(&claim.account, claim.index, claim.shares + 1).unwrap();
+            assert!(!verify(&proof, &root, inflated));
+            let moved = leaf_hash(&claim.account, claim.index + 1, claim.shares).unwrap();
+            assert!(count == 1 || !verify(&proof, &root, moved));
+        }
+    }
+
+    assert_eq!(
+        AirdropTree::from_shares(1, &BTreeMap::new()),
+        Err(Error::NoHolders)
+    );
+    assert_eq!(
+        leaf_hash("not an address", 0, 1),
+        Err(Error::InvalidAccount("not an address".into()))
+    );
+}
+
+fn encode(scenario: &Scenario, val: &Val) -> String {
+    ScVal::try_from_val(&scenario.env, val)
+        .unwrap()
+        .to_xdr_base64(Limits::none())
+        .unwrap()
+}
+
+// Records the events of the last invocation as `getEvents` entries.
+fn capture(scenario: &Scenario, events: &mut Vec<RpcEvent>) {
+    let ledger = scenario.env.ledger().sequence();
+    for (contract, topics, data) in scenario.env.events().all().iter() {
+        events.push(RpcEvent {
+            id: format!("{:019}-{:010}", u64::from(ledger) << 32, events.len()),
+            ledger,
+            contract_id: contract.to_string().to_string(),
+            topic: topics
+                .iter()
+                .map(|topic| encode(scenario, &topic))
+                .collect(),
+            value: encode(scenario, &data),
+        });
+    }
+}
+
+#[test]
+fn exported_events_fund_claims_for_every_holder() {
+    let scenario = Scenario::builder().build();
+    let env = &scenario.env;
+    let mut rng = Rng(0xa1d0);
+    let mut events = Vec::new();
+    let holders: Vec<Address> = (0..12).map(|_| Address::generate(env)).collect();
+    for holder in &holders {
+        scenario.advance_ledgers(1);
+        scenario.deposit(holder, (rng.next() % 50_000) as i128 + 1);
+        capture(&scenario, &mut events);
+    }
+    for _ in 0..20 {
+        scenario.advance_ledgers(1);
+        let from = &holders[rng.next() as usize % holders.len()];
+        let to = &holders[rng.next() as usize % holders.len()];
+        let amount = scenario.rusd.balance(from) / 3;
+        if amount > 0 {
+            scenario.rusd.transfer(from, to, &amount);
+            capture(&scenario, &mut events);
+        }
+    }
+    scenario.advance_ledgers(1);
+    scenario
+        .rusd
+        .request_redeem(&holders[0], &(scenario.rusd.shares_of(&holders[0]) / 2));
+    capture(&scenario, &mut events);
+    scenario.advance_ledgers(1);
+    scenario.simulate_yield(7_777);
+    capture(&scenario, &mut events);
+    let snapshot_ledger = env.ledger().sequence();
+
+    // Activity after the snapshot does not change the tree.
+    scenario.advance_ledgers(1);
+    scenario.rusd.transfer(&holders[1], &holders[2], &1);
+    capture(&scenario, &mut events);
+
+    let path = std::env::temp_dir().join(format!("rusd-airdrop-{}.json", std::process::id()));
+    let export =
+        serde_json::json!({ "result": { "events": events, "latestLedger": snapshot_ledger + 1 } });
+    std::fs::write(&path, export.to_string()).unwrap();
+    let events = load_fixture(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let rusd_id = scenario.rusd.address.to_string().to_string();
+    let excluded = holders[11].to_string().to_string();
+    let tree =
+        AirdropTree::from_events(&events, &rusd_id, snapshot_ledger, std::slice::from_ref(&excluded)).unwrap();
+    assert_eq!(tree.claims.len(), 11);
+    assert!(tree
+        .claims
+        .iter()
+        .all(|claim| claim.account != excluded && claim.account != rusd_id));
+    let shares_at_snapshot: i128 = holders[..11]
+        .iter()
+        .map(|holder| scenario.rusd.shares_of(holder))
+        .sum();
+    assert_eq!(tree.total_shares, shares_at_snapshot);
+
+    let admin = Address::generate(env);
+    let distributor = Address::generate(env);
+    let airdrop = RUsdAirdropClient::new(
+        env,
+        &env.register(RUsdAirdrop, (admin.clone(), scenario.rusd.address.clone())),
+    );
+    airdrop.grant_role(&admin, &distributor, &Symbol::new(env, "distributor"));
+    scenario.deposit(&distributor, 100_000);
+    let root = BytesN::from_array(env, &from_hex(&tree.root).unwrap());
+    let id = airdrop.create_distribution(
+        &distributor,
+        &root,
+        &tree.snapshot_ledger,
+        &tree.total_shares,
+        &100_000,
+    );
+    let bonus_shares = airdrop.distribution(&id).unwrap().bonus_shares;
+
+    let mut paid = 0;
+    for claim in &tree.claims {
+        let account = Address::from_str(env, &claim.account);
+        let proof = SorobanVec::from_iter(
+            env,
+            claim
+                .proof
+                .iter()
+                .map(|hex| BytesN::from_array(env, &from_hex(hex).unwrap())),
+        );
+        let shares = airdrop.claim(&id, &claim.index, &account, &claim.shares, &proof);
+        assert_eq!(shares, bonus_shares * claim.shares / tree.total_shares);
+        paid += shares;
+        assert_eq!(
+            airdrop.try_claim(&id, &claim.index, &account, &claim.shares, &proof),
+            Err(Ok(AirdropError::AlreadyClaimed.into()))
+        );
+    }
+    let dust = bonus_shares - paid;
+    assert!((0..tree.claims.len() as i128).contains(&dust));
+    assert_eq!(scenario.rusd.shares_of(&airdrop.address), dust);
+}
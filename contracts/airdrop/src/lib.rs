@@ -0,0 +1,267 @@
+#![no_std]
+
+use rusd_math::mul_div_floor;
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype,
+    panic_with_error, vec, xdr::ToXdr, Address, BytesN, Env, Vec,
+};
+use stellar_access::access_control::{self as access_control, AccessControl};
+use stellar_contract_utils::crypto::{merkle::Verifier, sha256::Sha256};
+use stellar_macros::{default_impl, only_role};
+
+const CLAIM_WORD_BITS: u32 = 128;
+const DAY_IN_LEDGERS: u32 = 17_280;
+const CLAIMED_EXTEND_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const CLAIMED_TTL_THRESHOLD: u32 = CLAIMED_EXTEND_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracterror]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    NotInitialized = 2,
+    InvalidDistribution = 3,
+    DistributionNotFound = 4,
+    AlreadyClaimed = 5,
+    InvalidProof = 6,
+    BonusNotReceived = 7,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Token,
+    DistributionCount,
+    Distribution(u32),
+    Claimed(u32, u32),
+}
+
+// Leaf of a snapshot tree, hashed as `sha256(leaf.to_xdr())` and paired with
+// sorted (commutative) hashing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Leaf {
+    pub account: Address,
+    pub index: u32,
+    pub shares: i128,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Distribution {
+    pub root: BytesN<32>,
+    pub snapshot_ledger: u32,
+    pub total_shares: i128,
+    pub bonus_shares: i128,
+    pub claimed_shares: i128,
+}
+
+#[contractclient(name = "RebasingTokenClient")]
+pub trait RebasingTokenInterface {
+    fn transfer(env: Env, from: Address, to: Address, amount: i128);
+    fn batch_transfer_shares(env: Env, from: Address, payouts: Vec<(Address, i128)>);
+    fn shares_of(env: Env, account: Address) -> i128;
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DistributionCreated {
+    #[topic]
+    pub id: u32,
+    pub root: BytesN<32>,
+    pub snapshot_ledger: u32,
+    pub total_shares: i128,
+    pub bonus_shares: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BonusClaimed {
+    #[topic]
+    pub id: u32,
+    #[topic]
+    pub account: Address,
+    pub index: u32,
+    pub shares: i128,
+}
+
+#[contract]
+pub struct RUsdAirdrop;
+
+#[contractimpl]
+impl RUsdAirdrop {
+    // Runs as part of deployment, so nobody can take the admin role (and with
+    // it the distributor role and token address) before the deployer does.
+    pub fn __constructor(env: Env, admin: Address, token: Address) {
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Token, &token);
+        access_control::set_admin(&env, &admin);
+    }
+
+    pub fn token(env: Env) -> Address {
+        read_token(&env)
+    }
+
+    // Pulls `amount` rUSD from the distributor and shares it pro rata among
+    // the leaves of `root`, whose shares must add up to `total_shares`.
+    #[only_role(distributor, "distributor")]
+    pub fn create_distribution(
+        env: Env,
+        distributor: Address,
+        root: BytesN<32>,
+        snapshot_ledger: u32,
+        total_shares: i128,
+        amount: i128,
+    ) -> u32 {
+        if total_shares <= 0 || amount <= 0 {
+            panic_with_error!(&env, Error::InvalidDistribution);
+        }
+        let token = RebasingTokenClient::new(&env, &read_token(&env));
+        let contract = env.current_contract_address();
+        let shares_before = token.shares_of(&contract);
+        token.transfer(&distributor, &contract, &amount);
+        let bonus_shares = token.shares_of(&contract) - shares_before;
+        if bonus_shares <= 0 {
+            panic_with_error!(&env, Error::BonusNotReceived);
+        }
+
+        let id = read_count(&env);
+        let distribution = Distribution {
+            root: root.clone(),
+            snapshot_ledger,
+            total_shares,
+            bonus_shares,
+            claimed_shares: 0,
+        };
+        write_distribution(&env, id, &distribution);
+        env.storage()
+            .instance()
+            .set(&DataKey::DistributionCount, &(id + 1));
+        DistributionCreated {
+            id,
+            root,
+            snapshot_ledger,
+            total_shares,
+            bonus_shares,
+        }
+        .publish(&env);
+        id
+    }
+
+    // Pays `account` its pro-rata bonus in rUSD shares, so the bonus keeps
+    // earning yield until claimed. Anyone may submit a claim; the payout
+    // always goes to the leaf's account. Returns the shares paid.
+    pub fn claim(
+        env: Env,
+        id: u32,
+        index: u32,
+        account: Address,
+        shares: i128,
+        proof: Vec<BytesN<32>>,
+    ) -> i128 {
+        let mut distribution = read_distribution(&env, id);
+        let (word_key, bit) = claim_slot(id, index);
+        let word = read_word(&env, &word_key);
+        if word & bit != 0 {
+            panic_with_error!(&env, Error::AlreadyClaimed);
+        }
+        let leaf = Leaf {
+            account: account.clone(),
+            index,
+            shares,
+        };
+        let leaf_hash = env.crypto().sha256(&leaf.to_xdr(&env)).to_bytes();
+        if shares <= 0
+            || !Verifier::<Sha256>::verify(&env, proof, distribution.root.clone(), leaf_hash)
+        {
+            panic_with_error!(&env, Error::InvalidProof);
+        }
+        env.storage().persistent().set(&word_key, &(word | bit));
+        extend_word(&env, &word_key);
+
+        let payout = mul_div_floor(distribution.bonus_shares, shares, distribution.total_shares)
+            .unwrap_or_else(|_| panic_with_error!(&env, Error::InvalidDistribution));
+        if distribution.claimed_shares + payout > distribution.bonus_shares {
+            panic_with_error!(&env, Error::InvalidDistribution);
+        }
+        distribution.claimed_shares += payout;
+        write_distribution(&env, id, &distribution);
+        if payout > 0 {
+            let contract = env.current_contract_address();
+            RebasingTokenClient::new(&env, &read_token(&env))
+                .batch_transfer_shares(&contract, &vec![&env, (account.clone(), payout)]);
+        }
+        BonusClaimed {
+            id,
+            account,
+            index,
+            shares: payout,
+        }
+        .publish(&env);
+        payout
+    }
+
+    pub fn is_claimed(env: Env, id: u32, index: u32) -> bool {
+        let (word_key, bit) = claim_slot(id, index);
+        read_word(&env, &word_key) & bit != 0
+    }
+
+    pub fn distribution(env: Env, id: u32) -> Option<Distribution> {
+        env.storage().persistent().get(&DataKey::Distribution(id))
+    }
+
+    pub fn distribution_count(env: Env) -> u32 {
+        read_count(&env)
+    }
+}
+
+#[default_impl]
+#[contractimpl]
+impl AccessControl for RUsdAirdrop {}
+
+fn read_token(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Token)
+        .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+}
+
+fn read_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::DistributionCount)
+        .unwrap_or(0)
+}
+
+fn read_distribution(env: &Env, id: u32) -> Distribution {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Distribution(id))
+        .unwrap_or_else(|| panic_with_error!(env, Error::DistributionNotFound))
+}
+
+fn write_distribution(env: &Env, id: u32, distribution: &Distribution) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Distribution(id), distribution);
+}
+
+// Claims are tracked in 128-bit words: leaf `index` is bit `index % 128` of
+// word `index / 128`.
+fn claim_slot(id: u32, index: u32) -> (DataKey, u128) {
+    (
+        DataKey::Claimed(id, index / CLAIM_WORD_BITS),
+        1u128 << (index % CLAIM_WORD_BITS),
+    )
+}
+
+fn read_word(env: &Env, key: &DataKey) -> u128 {
+    env.storage().persistent().get(key).unwrap_or(0)
+}
+
+fn extend_word(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, CLAIMED_TTL_THRESHOLD, CLAIMED_EXTEND_AMOUNT);
+}
+
+#[cfg(test)]
+mod test;
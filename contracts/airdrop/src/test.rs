@@ -0,0 +1,223 @@
+#![cfg(test)]
+
+extern crate std;
+
+use rusd_rebasing_token::testutils::Scenario;
+use soroban_sdk::{
+    testutils::Address as _, vec, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Symbol, Vec,
+};
+
+use crate::{Error, Leaf, RUsdAirdrop, RUsdAirdropClient};
+
+fn leaf_hash(env: &Env, leaf: &Leaf) -> BytesN<32> {
+    env.crypto().sha256(&leaf.clone().to_xdr(env)).to_bytes()
+}
+
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (low, high) = if a > b { (b, a) } else { (a, b) };
+    let mut data = Bytes::from(low.clone());
+    data.append(&Bytes::from(high.clone()));
+    env.crypto().sha256(&data).to_bytes()
+}
+
+// Root and proofs for a four-leaf tree.
+fn tree(env: &Env, leaves: &[Leaf; 4]) -> (BytesN<32>, std::vec::Vec<Vec<BytesN<32>>>) {
+    let hashes: std::vec::Vec<_> = leaves.iter().map(|leaf| leaf_hash(env, leaf)).collect();
+    let left = hash_pair(env, &hashes[0], &hashes[1]);
+    let right = hash_pair(env, &hashes[2], &hashes[3]);
+    let proofs = (0..4)
+        .map(|i| {
+            let uncle = if i < 2 { right.clone() } else { left.clone() };
+            vec![env, hashes[i ^ 1].clone(), uncle]
+        })
+        .collect();
+    (hash_pair(env, &left, &right), proofs)
+}
+
+struct Setup {
+    scenario: Scenario,
+    airdrop: RUsdAirdropClient<'static>,
+    distributor: Address,
+    leaves: [Leaf; 4],
+}
+
+fn setup() -> Setup {
+    let scenario = Scenario::builder().build();
+    let env = scenario.env.clone();
+    let admin = Address::generate(&env);
+    let airdrop = RUsdAirdropClient::new(
+        &env,
+        &env.register(RUsdAirdrop, (admin.clone(), scenario.rusd.address.clone())),
+    );
+    let distributor = Address::generate(&env);
+    airdrop.grant_role(&admin, &distributor, &Symbol::new(&env, "distributor"));
+    scenario.deposit(&distributor, 10_000);
+
+    let actors = &scenario.actors;
+    let leaves = [
+        (&actors.alice, 0, 500),
+        (&actors.bob, 1, 300),
+        (&actors.carol, 130, 199),
+        (&actors.donor, 131, 1),
+    ]
+    .map(|(account, index, shares)| Leaf {
+        account: account.clone(),
+        index,
+        shares,
+    });
+    Setup {
+        scenario,
+        airdrop,
+        distributor,
+        leaves,
+    }
+}
+
+#[test]
+fn holders_claim_pro_rata_bonus_once() {
+    let Setup {
+        scenario,
+        airdrop,
+        distributor,
+        leaves,
+    } = setup();
+    let env = &scenario.env;
+    let (root, proofs) = tree(env, &leaves);
+    let id = airdrop.create_distribution(&distributor, &root, &100, &1_000, &1_000);
+    assert_eq!(id, 0);
+    assert_eq!(scenario.rusd.balance(&airdrop.address), 1_000);
+
+    // Yield earned before the claim stays with the bonus.
+    scenario.simulate_yield(10_000);
+    let mut paid = 0;
+    for (leaf, proof) in leaves.iter().zip(&proofs) {
+        let before = scenario.rusd.shares_of(&leaf.account);
+        let shares = airdrop.claim(&id, &leaf.index, &leaf.account, &leaf.shares, proof);
+        assert_eq!(shares, 1_000 * leaf.shares / 1_000);
+        assert_eq!(scenario.rusd.shares_of(&leaf.account) - before, shares);
+        assert!(airdrop.is_claimed(&id, &leaf.index));
+        paid += shares;
+    }
+    assert_eq!(scenario.rusd.balance(&leaves[0].account), 1_000);
+    assert_eq!(paid, 1_000);
+    assert_eq!(airdrop.distribution(&id).unwrap().claimed_shares, 1_000);
+    assert!(!airdrop.is_claimed(&id, &2));
+    assert!(!airdrop.is_claimed(&id, &129));
+
+    assert_eq!(
+        airdrop.try_claim(&id, &0, &leaves[0].account, &500, &proofs[0]),
+        Err(Ok(Error::AlreadyClaimed.into()))
+    );
+}
+
+#[test]
+fn claims_need_a_valid_proof() {
+    let Setup {
+        scenario,
+        airdrop,
+        distributor,
+        leaves,
+    } = setup();
+    let env = &scenario.env;
+    let (root, proofs) = tree(env, &leaves);
+    let id = airdrop.create_distribution(&distributor, &root, &100, &1_000, &2_000);
+    let alice = &leaves[0].account;
+
+    assert_eq!(
+        airdrop.try_claim(&id, &0, alice, &501, &proofs[0]),
+        Err(Ok(Error::InvalidProof.into()))
+    );
+    assert_eq!(
+        airdrop.try_claim(&id, &1, alice, &500, &proofs[0]),
+        Err(Ok(Error::InvalidProof.into()))
+    );
+    assert_eq!(
+        airdrop.try_claim(&id, &0, &leaves[1].account, &500, &proofs[0]),
+        Err(Ok(Error::InvalidProof.into()))
+    );
+    assert_eq!(
+        airdrop.try_claim(&id, &0, alice, &500, &proofs[1]),
+        Err(Ok(Error::InvalidProof.into()))
+    );
+    assert_eq!(
+        airdrop.try_claim(&1, &0, alice, &500, &proofs[0]),
+        Err(Ok(Error::DistributionNotFound.into()))
+    );
+    assert!(!airdrop.is_claimed(&id, &0));
+    assert_eq!(airdrop.claim(&id, &0, alice, &500, &proofs[0]), 1_000);
+}
+
+#[test]
+fn only_distributors_fund_distributions() {
+    let Setup {
+        scenario,
+        airdrop,
+        distributor,
+        leaves,
+    } = setup();
+    let env = &scenario.env;
+    let (root, _) = tree(env, &leaves);
+    let outsider = &scenario.actors.alice;
+    scenario.deposit(outsider, 100);
+
+    assert!(airdrop
+        .try_create_distribution(outsider, &root, &100, &1_000, &100)
+        .is_err());
+    assert_eq!(
+        airdrop.try_create_distribution(&distributor, &root, &100, &0, &100),
+        Err(Ok(Error::InvalidDistribution.into()))
+    );
+    assert_eq!(
+        airdrop.try_create_distribution(&distributor, &root, &100, &1_000, &0),
+        Err(Ok(Error::InvalidDistribution.into()))
+    );
+    assert!(airdrop
+        .try_create_distribution(&distributor, &root, &100, &1_000, &20_000)
+        .is_err());
+    assert_eq!(airdrop.distribution_count(), 0);
+
+    airdrop.create_distribution(&distributor, &root, &100, &1_000, &400);
+    airdrop.create_distribution(&distributor, &root, &100, &1_000, &600);
+    assert_eq!(airdrop.distribution_count(), 2);
+    assert_eq!(airdrop.distribution(&1).unwrap().bonus_shares, 600);
+}
+
+#[test]
+fn third_party_cannot_reconfigure_a_deployed_airdrop() {
+    let Setup {
+        scenario,
+        airdrop,
+        distributor,
+        ..
+    } = setup();
+    let env = &scenario.env;
+    let attacker = Address::generate(env);
+    let args = vec![
+        env,
+        attacker.into_val(env),
+        attacker.clone().into_val(env),
+    ];
+    for function in ["init", "__constructor"] {
+        let result = env.try_invoke_contract::<(), Error>(
+            &airdrop.address,
+            &Symbol::new(env, function),
+            args.clone(),
+        );
+        assert!(result.is_err());
+    }
+    assert_eq!(airdrop.token(), scenario.rusd.address);
+    assert!(airdrop
+        .has_role(&distributor, &Symbol::new(env, "distributor"))
+        .is_some());
+    assert!(airdrop
+        .has_role(&attacker, &Symbol::new(env, "distributor"))
+        .is_none());
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn constructor_requires_admin_auth() {
+    let env = Env::default();
+    let token = Address::generate(&env);
+    env.register(RUsdAirdrop, (Address::generate(&env), token));
+}
@@ -0,0 +1,286 @@
+use std::{collections::BTreeMap, env, fs, path::PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use stellar_xdr::curr::{
+    ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef, ScSpecUdtStructV0, ScSpecUdtUnionCaseV0,
+    ScSpecUdtUnionV0,
+};
+
+// Generates a typed binding for every token entrypoint, and the types they
+// use, from the contract spec embedded in the token wasm. A changed contract
+// signature then breaks the CLI build instead of a transaction.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let entries = soroban_spec::read::from_wasm(rusd_token_wasm::WASM)
+        .expect("the token wasm has no contract spec");
+
+    let mut udts = BTreeMap::new();
+    let mut functions = Vec::new();
+    for entry in &entries {
+        match entry {
+            ScSpecEntry::FunctionV0(function) => functions.push(function),
+            ScSpecEntry::UdtStructV0(udt) => {
+                udts.entry(udt.name.to_utf8_string_lossy())
+                    .or_insert_with(Vec::new)
+                    .push(entry);
+            }
+            ScSpecEntry::UdtUnionV0(udt) => {
+                udts.entry(udt.name.to_utf8_string_lossy())
+                    .or_insert_with(Vec::new)
+                    .push(entry);
+            }
+            _ => {}
+        }
+    }
+
+    // Only the types reachable from an entrypoint are generated; the spec also
+    // carries storage keys and library types the CLI never sees.
+    let mut used = BTreeMap::new();
+    let mut pending: Vec<&ScSpecTypeDef> = functions
+        .iter()
+        .flat_map(|function| {
+            function
+                .inputs
+                .iter()
+                .map(|input| &input.type_)
+                .chain(function.outputs.iter())
+        })
+        .collect();
+    while let Some(type_) = pending.pop() {
+        for name in udt_names(type_) {
+            if used.contains_key(&name) {
+                continue;
+            }
+            let entry = match udts.get(&name).map(Vec::as_slice) {
+                Some([entry]) => *entry,
+                Some(_) => panic!("the spec defines `{name}` more than once"),
+                None => panic!("the spec does not define `{name}`"),
+            };
+            match entry {
+                ScSpecEntry::UdtStructV0(udt) => {
+                    pending.extend(udt.fields.iter().map(|field| &field.type_))
+                }
+                ScSpecEntry::UdtUnionV0(udt) => {
+                    for case in udt.cases.iter() {
+                        if let ScSpecUdtUnionCaseV0::TupleV0(case) = case {
+                            pending.extend(case.type_.iter());
+                        }
+                    }
+                }
+                _ => unreachable!(),
+            }
+            used.insert(name, entry);
+        }
+    }
+
+    let types = used.values().map(|entry| match entry {
+        ScSpecEntry::UdtStructV0(udt) => struct_binding(udt),
+        ScSpecEntry::UdtUnionV0(udt) => union_binding(udt),
+        _ => unreachable!(),
+    });
+    let functions = functions.iter().map(|function| function_binding(function));
+    let bindings = quote! {
+        #(#types)*
+        #(#functions)*
+    };
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("bindings.rs");
+    fs::write(out, bindings.to_string()).unwrap();
+}
+
+fn udt_names(type_: &ScSpecTypeDef) -> Vec<String> {
+    match type_ {
+        ScSpecTypeDef::Option(option) => udt_names(&option.value_type),
+        ScSpecTypeDef::Vec(vec) => udt_names(&vec.element_type),
+        ScSpecTypeDef::Tuple(tuple) => tuple.value_types.iter().flat_map(udt_names).collect(),
+        ScSpecTypeDef::Udt(udt) => vec![udt.name.to_utf8_string_lossy()],
+        _ => Vec::new(),
+    }
+}
+
+fn rust_type(type_: &ScSpecTypeDef) -> TokenStream {
+    match type_ {
+        ScSpecTypeDef::Bool => quote!(bool),
+        ScSpecTypeDef::Void => quote!(()),
+        ScSpecTypeDef::U32 => quote!(u32),
+        ScSpecTypeDef::I32 => quote!(i32),
+        ScSpecTypeDef::U64 => quote!(u64),
+        ScSpecTypeDef::I64 => quote!(i64),
+        ScSpecTypeDef::U128 => quote!(u128),
+        ScSpecTypeDef::I128 => quote!(i128),
+        ScSpecTypeDef::Address => quote!(ScAddress),
+        ScSpecTypeDef::String => quote!(String),
+        ScSpecTypeDef::Symbol => quote!(ScSymbol),
+        ScSpecTypeDef::Bytes => quote!(ScBytes),
+        ScSpecTypeDef::Option(option) => {
+            let value = rust_type(&option.value_type);
+            quote!(Option<#value>)
+        }
+        ScSpecTypeDef::Vec(vec) => {
+            let element = rust_type(&vec.element_type);
+            quote!(Vec<#element>)
+        }
+        ScSpecTypeDef::Tuple(tuple) => {
+            let values = tuple.value_types.iter().map(rust_type);
+            quote!((#(#values,)*))
+        }
+        ScSpecTypeDef::Udt(udt) => {
+            let name = format_ident!("{}", udt.name.to_utf8_string_lossy());
+            quote!(#name)
+        }
+        other => panic!("no binding for spec type {other:?}"),
+    }
+}
+
+fn output_type(function: &ScSpecFunctionV0) -> TokenStream {
+    match function.outputs.as_slice() {
+        [] => quote!(()),
+        [output] => rust_type(output),
+        _ => panic!(
+            "`{}` has several outputs",
+            function.name.0.to_utf8_string_lossy()
+        ),
+    }
+}
+
+fn function_binding(function: &ScSpecFunctionV0) -> TokenStream {
+    let name = function.name.0.to_utf8_string_lossy();
+    let ident = format_ident!("{}", name);
+    let params = function.inputs.iter().map(|input| {
+        let param = format_ident!("{}", input.name.to_utf8_string_lossy());
+        let type_ = rust_type(&input.type_);
+        quote!(#param: #type_)
+    });
+    let args = function.inputs.iter().map(|input| {
+        let param = format_ident!("{}", input.name.to_utf8_string_lossy());
+        quote!(#param.to_sc_val())
+    });
+    let output = output_type(function);
+    quote! {
+        pub fn #ident(#(#params),*) -> Call<#output> {
+            Call::new(#name, vec![#(#args),*])
+        }
+    }
+}
+
+fn struct_binding(udt: &ScSpecUdtStructV0) -> TokenStream {
+    let name = udt.name.to_utf8_string_lossy();
+    let ident = format_ident!("{}", name);
+    // Struct fields are encoded as a map keyed by field name, in key order.
+    let mut fields: Vec<_> = udt
+        .fields
+        .iter()
+        .map(|field| (field.name.to_utf8_string_lossy(), &field.type_))
+        .collect();
+    fields.sort_by(|a, b| a.0.cmp(&b.0));
+    if fields.iter().any(|(field, _)| field.parse::<u32>().is_ok()) {
+        panic!("no binding for tuple struct `{name}`");
+    }
+    let keys: Vec<_> = fields.iter().map(|(field, _)| field.as_str()).collect();
+    let idents: Vec<_> = fields
+        .iter()
+        .map(|(field, _)| format_ident!("{}", field))
+        .collect();
+    let types = fields.iter().map(|(_, type_)| rust_type(type_));
+    quote! {
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        pub struct #ident {
+            #(pub #idents: #types,)*
+        }
+
+        impl ToScVal for #ident {
+            fn to_sc_val(&self) -> ScVal {
+                struct_to_sc_val(vec![#((#keys, self.#idents.to_sc_val())),*])
+            }
+        }
+
+        impl FromScVal for #ident {
+            fn from_sc_val(val: ScVal) -> Result<Self, String> {
+                let [#(#idents),*] = struct_fields(val, [#(#keys),*])?;
+                Ok(#ident {
+                    #(#idents: FromScVal::from_sc_val(#idents)?,)*
+                })
+            }
+        }
+    }
+}
+
+fn union_binding(udt: &ScSpecUdtUnionV0) -> TokenStream {
+    let name = udt.name.to_utf8_string_lossy();
+    let ident = format_ident!("{}", name);
+    let cases: Vec<(String, Vec<&ScSpecTypeDef>)> = udt
+        .cases
+        .iter()
+        .map(|case| match case {
+            ScSpecUdtUnionCaseV0::VoidV0(case) => (case.name.to_utf8_string_lossy(), Vec::new()),
+            ScSpecUdtUnionCaseV0::TupleV0(case) => (
+                case.name.to_utf8_string_lossy(),
+                case.type_.iter().collect(),
+            ),
+        })
+        .collect();
+
+    let variants = cases.iter().map(|(case, types)| {
+        let variant = format_ident!("{}", case);
+        if types.is_empty() {
+            quote!(#variant)
+        } else {
+            let types = types.iter().map(|type_| rust_type(type_));
+            quote!(#variant(#(#types),*))
+        }
+    });
+    let encode = cases.iter().map(|(case, types)| {
+        let variant = format_ident!("{}", case);
+        let values: Vec<_> = (0..types.len()).map(|i| format_ident!("v{}", i)).collect();
+        let pattern = if types.is_empty() {
+            quote!(#ident::#variant)
+        } else {
+            quote!(#ident::#variant(#(#values),*))
+        };
+        quote!(#pattern => union_to_sc_val(#case, vec![#(#values.to_sc_val()),*]))
+    });
+    let decode = cases.iter().map(|(case, types)| {
+        let variant = format_ident!("{}", case);
+        let values: Vec<_> = (0..types.len()).map(|i| format_ident!("v{}", i)).collect();
+        if types.is_empty() {
+            quote! {
+                #case => {
+                    let [] = union_values(values)?;
+                    Ok(#ident::#variant)
+                }
+            }
+        } else {
+            quote! {
+                #case => {
+                    let [#(#values),*] = union_values(values)?;
+                    Ok(#ident::#variant(#(FromScVal::from_sc_val(#values)?),*))
+                }
+            }
+        }
+    });
+    quote! {
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        pub enum #ident {
+            #(#variants,)*
+        }
+
+        impl ToScVal for #ident {
+            fn to_sc_val(&self) -> ScVal {
+                match self {
+                    #(#encode,)*
+                }
+            }
+        }
+
+        impl FromScVal for #ident {
+            fn from_sc_val(val: ScVal) -> Result<Self, String> {
+                let (case, values) = union_case(val)?;
+                match case.as_str() {
+                    #(#decode)*
+                    other => Err(format!("unknown {} case `{}`", #name, other)),
+                }
+            }
+        }
+    }
+}
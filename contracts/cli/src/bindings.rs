@@ -0,0 +1,303 @@
+// Typed calls for every token entrypoint, generated by `build.rs` from the
+// contract spec in the token wasm. The CLI uses only some of them.
+#![allow(dead_code)]
+
+use std::marker::PhantomData;
+
+use stellar_xdr::curr::{
+    Int128Parts, ScAddress, ScBytes, ScMap, ScMapEntry, ScString, ScSymbol, ScVal, ScVec,
+    UInt128Parts,
+};
+
+pub trait ToScVal {
+    fn to_sc_val(&self) -> ScVal;
+}
+
+pub trait FromScVal: Sized {
+    fn from_sc_val(val: ScVal) -> Result<Self, String>;
+}
+
+// One contract invocation: the function, its encoded arguments and the type
+// its result decodes to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call<T> {
+    pub function: &'static str,
+    pub args: Vec<ScVal>,
+    output: PhantomData<T>,
+}
+
+impl<T> Call<T> {
+    fn new(function: &'static str, args: Vec<ScVal>) -> Self {
+        Call {
+            function,
+            args,
+            output: PhantomData,
+        }
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+fn unexpected<T>(expected: &str, val: &ScVal) -> Result<T, String> {
+    Err(format!("expected {expected}, got {val:?}"))
+}
+
+macro_rules! scalar {
+    ($type:ty, $variant:ident) => {
+        impl ToScVal for $type {
+            fn to_sc_val(&self) -> ScVal {
+                ScVal::$variant(*self)
+            }
+        }
+
+        impl FromScVal for $type {
+            fn from_sc_val(val: ScVal) -> Result<Self, String> {
+                match val {
+                    ScVal::$variant(value) => Ok(value),
+                    other => unexpected(stringify!($type), &other),
+                }
+            }
+        }
+    };
+}
+
+scalar!(bool, Bool);
+scalar!(u32, U32);
+scalar!(i32, I32);
+scalar!(u64, U64);
+scalar!(i64, I64);
+
+impl ToScVal for () {
+    fn to_sc_val(&self) -> ScVal {
+        ScVal::Void
+    }
+}
+
+impl FromScVal for () {
+    fn from_sc_val(val: ScVal) -> Result<Self, String> {
+        match val {
+            ScVal::Void => Ok(()),
+            other => unexpected("void", &other),
+        }
+    }
+}
+
+impl ToScVal for u128 {
+    fn to_sc_val(&self) -> ScVal {
+        ScVal::U128(UInt128Parts {
+            hi: (*self >> 64) as u64,
+            lo: *self as u64,
+        })
+    }
+}
+
+impl FromScVal for u128 {
+    fn from_sc_val(val: ScVal) -> Result<Self, String> {
+        match val {
+            ScVal::U128(parts) => Ok((u128::from(parts.hi) << 64) | u128::from(parts.lo)),
+            other => unexpected("u128", &other),
+        }
+    }
+}
+
+impl ToScVal for i128 {
+    fn to_sc_val(&self) -> ScVal {
+        ScVal::I128(Int128Parts {
+            hi: (*self >> 64) as i64,
+            lo: *self as u64,
+        })
+    }
+}
+
+impl FromScVal for i128 {
+    fn from_sc_val(val: ScVal) -> Result<Self, String> {
+        match val {
+            ScVal::I128(parts) => Ok((i128::from(parts.hi) << 64) | i128::from(parts.lo)),
+            other => unexpected("i128", &other),
+        }
+    }
+}
+
+impl ToScVal for ScAddress {
+    fn to_sc_val(&self) -> ScVal {
+        ScVal::Address(self.clone())
+    }
+}
+
+impl FromScVal for ScAddress {
+    fn from_sc_val(val: ScVal) -> Result<Self, String> {
+        match val {
+            ScVal::Address(address) => Ok(address),
+            other => unexpected("an address", &other),
+        }
+    }
+}
+
+impl ToScVal for ScSymbol {
+    fn to_sc_val(&self) -> ScVal {
+        ScVal::Symbol(self.clone())
+    }
+}
+
+impl FromScVal for ScSymbol {
+    fn from_sc_val(val: ScVal) -> Result<Self, String> {
+        match val {
+            ScVal::Symbol(symbol) => Ok(symbol),
+            other => unexpected("a symbol", &other),
+        }
+    }
+}
+
+impl ToScVal for ScBytes {
+    fn to_sc_val(&self) -> ScVal {
+        ScVal::Bytes(self.clone())
+    }
+}
+
+impl FromScVal for ScBytes {
+    fn from_sc_val(val: ScVal) -> Result<Self, String> {
+        match val {
+            ScVal::Bytes(bytes) => Ok(bytes),
+            other => unexpected("bytes", &other),
+        }
+    }
+}
+
+impl ToScVal for String {
+    fn to_sc_val(&self) -> ScVal {
+        ScVal::String(ScString(
+            self.as_str().try_into().expect("string over the XDR limit"),
+        ))
+    }
+}
+
+impl FromScVal for String {
+    fn from_sc_val(val: ScVal) -> Result<Self, String> {
+        match val {
+            ScVal::String(string) => string.0.to_utf8_string().map_err(|err| err.to_string()),
+            other => unexpected("a string", &other),
+        }
+    }
+}
+
+impl<T: ToScVal> ToScVal for Option<T> {
+    fn to_sc_val(&self) -> ScVal {
+        match self {
+            Some(value) => value.to_sc_val(),
+            None => ScVal::Void,
+        }
+    }
+}
+
+impl<T: FromScVal> FromScVal for Option<T> {
+    fn from_sc_val(val: ScVal) -> Result<Self, String> {
+        match val {
+            ScVal::Void => Ok(None),
+            other => T::from_sc_val(other).map(Some),
+        }
+    }
+}
+
+impl<T: ToScVal> ToScVal for Vec<T> {
+    fn to_sc_val(&self) -> ScVal {
+        vec_to_sc_val(self.iter().map(ToScVal::to_sc_val).collect())
+    }
+}
+
+impl<T: FromScVal> FromScVal for Vec<T> {
+    fn from_sc_val(val: ScVal) -> Result<Self, String> {
+        sc_vec(val)?.into_iter().map(T::from_sc_val).collect()
+    }
+}
+
+macro_rules! tuple {
+    ($($name:ident),+) => {
+        impl<$($name: ToScVal),+> ToScVal for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn to_sc_val(&self) -> ScVal {
+                let ($($name,)+) = self;
+                vec_to_sc_val(vec![$($name.to_sc_val()),+])
+            }
+        }
+
+        impl<$($name: FromScVal),+> FromScVal for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn from_sc_val(val: ScVal) -> Result<Self, String> {
+                let [$($name),+] = union_values(sc_vec(val)?)?;
+                Ok(($($name::from_sc_val($name)?,)+))
+            }
+        }
+    };
+}
+
+tuple!(A);
+tuple!(A, B);
+tuple!(A, B, C);
+
+fn symbol_val(name: &str) -> ScVal {
+    ScVal::Symbol(ScSymbol(
+        name.try_into().expect("symbol over the XDR limit"),
+    ))
+}
+
+fn vec_to_sc_val(values: Vec<ScVal>) -> ScVal {
+    ScVal::Vec(Some(ScVec(
+        values.try_into().expect("vector over the XDR limit"),
+    )))
+}
+
+fn sc_vec(val: ScVal) -> Result<Vec<ScVal>, String> {
+    match val {
+        ScVal::Vec(Some(values)) => Ok(values.0.into()),
+        other => unexpected("a vector", &other),
+    }
+}
+
+fn struct_to_sc_val(fields: Vec<(&str, ScVal)>) -> ScVal {
+    let entries: Vec<ScMapEntry> = fields
+        .into_iter()
+        .map(|(key, val)| ScMapEntry {
+            key: symbol_val(key),
+            val,
+        })
+        .collect();
+    ScVal::Map(Some(ScMap(
+        entries.try_into().expect("map over the XDR limit"),
+    )))
+}
+
+fn struct_fields<const N: usize>(val: ScVal, keys: [&str; N]) -> Result<[ScVal; N], String> {
+    let entries: Vec<ScMapEntry> = match val {
+        ScVal::Map(Some(map)) => map.0.into(),
+        other => return unexpected("a map", &other),
+    };
+    let names: Vec<ScVal> = keys.iter().map(|key| symbol_val(key)).collect();
+    if entries.len() != N
+        || entries
+            .iter()
+            .zip(&names)
+            .any(|(entry, key)| entry.key != *key)
+    {
+        return Err(format!("expected fields {keys:?}"));
+    }
+    union_values(entries.into_iter().map(|entry| entry.val).collect())
+}
+
+fn union_to_sc_val(case: &str, values: Vec<ScVal>) -> ScVal {
+    vec_to_sc_val(std::iter::once(symbol_val(case)).chain(values).collect())
+}
+
+fn union_case(val: ScVal) -> Result<(String, Vec<ScVal>), String> {
+    let mut values = sc_vec(val)?;
+    if values.is_empty() {
+        return Err("expected a union case".into());
+    }
+    let case = ScSymbol::from_sc_val(values.remove(0))?;
+    Ok((case.to_utf8_string_lossy(), values))
+}
+
+fn union_values<const N: usize>(values: Vec<ScVal>) -> Result<[ScVal; N], String> {
+    values
+        .try_into()
+        .map_err(|values: Vec<ScVal>| format!("expected {N} values, got {}", values.len()))
+}
@@ -0,0 +1,61 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Config {
+    pub network: Network,
+    pub account: Account,
+    pub contract: Contract,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Network {
+    pub rpc_url: String,
+    pub passphrase: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Account {
+    pub source: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Contract {
+    pub wasm: String,
+    pub underlying: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+    pub admin: Option<String>,
+    pub id: Option<String>,
+}
+
+impl Config {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        toml::from_str(input).map_err(|err| err.to_string())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let input = fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+        Self::parse(&input)
+    }
+
+    pub fn contract_id(&self) -> Result<&str, String> {
+        self.contract
+            .id
+            .as_deref()
+            .ok_or_else(|| "contract.id is not set; run `rusd-cli deploy` first".into())
+    }
+}
+
+// Sets `contract.id`, keeping the rest of the file (comments included) as written.
+pub fn store_contract_id(path: &Path, id: &str) -> Result<(), String> {
+    let input = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let mut document: toml_edit::DocumentMut = input
+        .parse()
+        .map_err(|err: toml_edit::TomlError| err.to_string())?;
+    document["contract"]["id"] = toml_edit::value(id);
+    fs::write(path, document.to_string()).map_err(|err| err.to_string())
+}
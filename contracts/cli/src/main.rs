@@ -0,0 +1,192 @@
+mod bindings;
+mod config;
+mod network;
+mod operator;
+mod sandbox;
+
+use std::{path::PathBuf, process::Command as Process};
+
+use clap::{Parser, Subcommand};
+
+use crate::{
+    config::Config,
+    network::{Http, Network, Transport},
+    operator::{format_rate, AdminAction, Metadata, Timelocked},
+};
+
+#[derive(Parser)]
+#[command(
+    name = "rusd-cli",
+    about = "Build, deploy and operate an rUSD instance"
+)]
+struct Cli {
+    /// Network, account and contract settings
+    #[arg(long, default_value = "rusd.toml")]
+    config: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Debug, PartialEq, Subcommand)]
+enum Command {
+    /// Build the token wasm
+    Build,
+    #[command(flatten)]
+    Contract(ContractCommand),
+}
+
+// Subcommands that talk to the network.
+#[derive(Clone, Debug, PartialEq, Subcommand)]
+enum ContractCommand {
+    /// Deploy the wasm with the configured metadata as constructor arguments,
+    /// then store the contract id in the config
+    Deploy,
+    /// Deposit underlying and mint rUSD
+    Mint {
+        #[arg(long)]
+        to: Option<String>,
+        /// Approve the token to pull the underlying first
+        #[arg(long)]
+        approve: bool,
+        amount: i128,
+    },
+    /// Burn rUSD and redeem underlying
+    Burn {
+        #[arg(long)]
+        from: Option<String>,
+        amount: i128,
+    },
+    Balance {
+        account: Option<String>,
+    },
+    /// Latest checkpointed exchange rate, or the APY over a window
+    Rate {
+        #[arg(long)]
+        window: Option<u32>,
+    },
+    /// Recognize vault yield and donations (`sync`)
+    Harvest,
+    /// Admin changes that apply immediately while no timelock is set
+    #[command(subcommand)]
+    Admin(AdminAction),
+    /// Queue an admin change behind the timelock and print its id
+    #[command(subcommand)]
+    Schedule(Timelocked),
+    /// Apply a scheduled change once its delay has passed
+    Execute {
+        id: u64,
+    },
+    /// Print the contract state as TOML
+    Dump,
+}
+
+fn run<T: Transport>(
+    command: &ContractCommand,
+    config: &Config,
+    network: &mut Network<T>,
+) -> Result<String, String> {
+    match command {
+        ContractCommand::Deploy => {
+            let admin = match &config.contract.admin {
+                Some(admin) => admin.clone(),
+                None => network.caller(),
+            };
+            network.deploy(&Metadata {
+                admin,
+                name: config.contract.name.clone(),
+                symbol: config.contract.symbol.clone(),
+                decimals: config.contract.decimals,
+            })
+        }
+        ContractCommand::Mint {
+            to,
+            approve,
+            amount,
+        } => {
+            let to = account_or_caller(network, to);
+            network.mint(&to, *amount, *approve)?;
+            Ok(network.balance(&to)?.to_string())
+        }
+        ContractCommand::Burn { from, amount } => {
+            let from = account_or_caller(network, from);
+            network.burn(&from, *amount)?;
+            Ok(network.balance(&from)?.to_string())
+        }
+        ContractCommand::Balance { account } => {
+            let account = account_or_caller(network, account);
+            Ok(network.balance(&account)?.to_string())
+        }
+        ContractCommand::Rate { window: None } => Ok(match network.rate()? {
+            Some(rate) => format_rate(rate),
+            None => "no checkpoint yet".into(),
+        }),
+        ContractCommand::Rate {
+            window: Some(window),
+        } => Ok(format!("{} bps", network.apy(*window)?)),
+        ContractCommand::Harvest => {
+            network.harvest()?;
+            Ok("synced".into())
+        }
+        ContractCommand::Admin(action) => {
+            network.admin(action)?;
+            Ok("done".into())
+        }
+        ContractCommand::Schedule(action) => Ok(network.schedule(action)?.to_string()),
+        ContractCommand::Execute { id } => {
+            network.execute(*id)?;
+            Ok("executed".into())
+        }
+        ContractCommand::Dump => Ok(network.dump()?.to_string()),
+    }
+}
+
+fn account_or_caller<T: Transport>(network: &Network<T>, account: &Option<String>) -> String {
+    match account {
+        Some(account) => account.clone(),
+        None => network.caller(),
+    }
+}
+
+fn build() -> Result<String, String> {
+    let status = Process::new("cargo")
+        .args([
+            "build",
+            "--target",
+            "wasm32v1-none",
+            "--release",
+            "-p",
+            "rusd-rebasing-token",
+        ])
+        .status()
+        .map_err(|err| err.to_string())?;
+    if !status.success() {
+        return Err(format!("cargo build failed: {status}"));
+    }
+    Ok("built".into())
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Build => build(),
+        Command::Contract(command) => Config::load(&cli.config).and_then(|config| {
+            let transport = Http::new(config.network.rpc_url.clone());
+            let mut network = Network::new(config.clone(), transport)?;
+            let output = run(command, &config, &mut network)?;
+            if *command == ContractCommand::Deploy {
+                config::store_contract_id(&cli.config, &output)?;
+            }
+            Ok(output)
+        }),
+    };
+    match result {
+        Ok(output) => println!("{output}"),
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,564 @@
+use std::{fs, path::PathBuf, thread, time::Duration};
+
+use ed25519_dalek::{Signer, SigningKey};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    AccountId, ContractExecutable, ContractIdPreimage, ContractIdPreimageFromAddress,
+    CreateContractArgsV2, DecoratedSignature, Hash, HostFunction, InvokeContractArgs,
+    InvokeHostFunctionOp, LedgerEntryData, LedgerKey, LedgerKeyAccount, Limits, Memo, MuxedAccount,
+    Operation, OperationBody, Preconditions, PublicKey, ReadXdr, ScAddress, ScSymbol, ScVal,
+    SequenceNumber, Signature, SignatureHint, SorobanAuthorizationEntry, SorobanCredentials,
+    SorobanTransactionData, Transaction, TransactionEnvelope, TransactionExt, TransactionMeta,
+    TransactionResult, TransactionV1Envelope, Uint256, WriteXdr,
+};
+
+use crate::{
+    bindings::{self, Call, FromScVal},
+    config::Config,
+    operator::{AdminAction, Metadata, StateDump, Timelocked},
+};
+
+const BASE_FEE: u32 = 100;
+const POLL_ATTEMPTS: u32 = 30;
+// About a day of ledgers, for the allowance `mint --approve` grants.
+const APPROVAL_LEDGERS: u32 = 17_280;
+
+pub trait Transport {
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, String>;
+}
+
+// JSON-RPC over HTTP to a Stellar RPC server.
+pub struct Http {
+    url: String,
+}
+
+impl Http {
+    pub fn new(url: impl Into<String>) -> Self {
+        Http { url: url.into() }
+    }
+}
+
+impl Transport for Http {
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = ureq::post(&self.url)
+            .send_json(request)
+            .map_err(|err| err.to_string())?
+            .into_json()
+            .map_err(|err| err.to_string())?;
+        if let Some(error) = response.get("error") {
+            return Err(error.to_string());
+        }
+        Ok(response["result"].clone())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Simulation {
+    error: Option<String>,
+    #[serde(default)]
+    transaction_data: String,
+    #[serde(default)]
+    min_resource_fee: String,
+    #[serde(default)]
+    results: Vec<SimulationResult>,
+}
+
+#[derive(Deserialize)]
+struct SimulationResult {
+    xdr: String,
+    #[serde(default)]
+    auth: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Submission {
+    status: String,
+    hash: String,
+    error_result_xdr: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionStatus {
+    status: String,
+    result_meta_xdr: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LedgerEntries {
+    entries: Vec<LedgerEntryResult>,
+}
+
+#[derive(Deserialize)]
+struct LedgerEntryResult {
+    xdr: String,
+}
+
+#[derive(Deserialize)]
+struct LatestLedger {
+    sequence: u32,
+}
+
+// Operates one deployed rUSD instance through the generated bindings, signing
+// every transaction with the configured source account.
+pub struct Network<T: Transport> {
+    config: Config,
+    transport: T,
+    key: SigningKey,
+}
+
+impl<T: Transport> Network<T> {
+    pub fn new(config: Config, transport: T) -> Result<Self, String> {
+        let key = signing_key(&config.account.source)?;
+        Ok(Network {
+            config,
+            transport,
+            key,
+        })
+    }
+
+    pub fn caller(&self) -> String {
+        stellar_strkey::ed25519::PublicKey(self.key.verifying_key().to_bytes()).to_string()
+    }
+
+    fn source(&self) -> AccountId {
+        AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+            self.key.verifying_key().to_bytes(),
+        )))
+    }
+
+    fn token(&self) -> Result<ScAddress, String> {
+        address(self.config.contract_id()?)
+    }
+
+    fn call<R: DeserializeOwned>(&mut self, method: &str, params: Value) -> Result<R, String> {
+        let result = self.transport.request(method, params)?;
+        serde_json::from_value(result).map_err(|err| format!("unexpected {method} result: {err}"))
+    }
+
+    fn latest_ledger(&mut self) -> Result<u32, String> {
+        self.call::<LatestLedger>("getLatestLedger", json!({}))
+            .map(|ledger| ledger.sequence)
+    }
+
+    fn sequence(&mut self) -> Result<i64, String> {
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: self.source(),
+        });
+        let entries: LedgerEntries =
+            self.call("getLedgerEntries", json!({ "keys": [to_base64(&key)?] }))?;
+        let Some(entry) = entries.entries.first() else {
+            return Err(format!(
+                "source account {} does not exist; fund it first",
+                self.caller()
+            ));
+        };
+        match from_base64(&entry.xdr)? {
+            LedgerEntryData::Account(account) => Ok(account.seq_num.0),
+            _ => Err("getLedgerEntries returned a non-account entry".into()),
+        }
+    }
+
+    fn transaction(
+        &self,
+        sequence: i64,
+        host_function: HostFunction,
+        auth: Vec<SorobanAuthorizationEntry>,
+        fee: u32,
+        ext: TransactionExt,
+    ) -> Result<Transaction, String> {
+        let operation = Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                host_function,
+                auth: auth.try_into().map_err(xdr_error)?,
+            }),
+        };
+        Ok(Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(self.key.verifying_key().to_bytes())),
+            fee,
+            seq_num: SequenceNumber(sequence + 1),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![operation].try_into().map_err(xdr_error)?,
+            ext,
+        })
+    }
+
+    fn simulate(&mut self, host_function: &HostFunction) -> Result<(ScVal, Simulation), String> {
+        let sequence = self.sequence()?;
+        let transaction = self.transaction(
+            sequence,
+            host_function.clone(),
+            Vec::new(),
+            BASE_FEE,
+            TransactionExt::V0,
+        )?;
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: transaction,
+            signatures: Default::default(),
+        });
+        let simulation: Simulation = self.call(
+            "simulateTransaction",
+            json!({ "transaction": to_base64(&envelope)? }),
+        )?;
+        if let Some(error) = simulation.error {
+            return Err(error);
+        }
+        let result = match simulation.results.first() {
+            Some(result) => from_base64(&result.xdr)?,
+            None => return Err("simulation returned no result".into()),
+        };
+        Ok((result, simulation))
+    }
+
+    fn view<R: FromScVal>(&mut self, call: Call<R>) -> Result<R, String> {
+        let host_function = invoke(self.token()?, call)?;
+        let (result, _) = self.simulate(&host_function)?;
+        R::from_sc_val(result)
+    }
+
+    fn send<R: FromScVal>(&mut self, call: Call<R>) -> Result<R, String> {
+        let contract = self.token()?;
+        self.send_to(contract, call)
+    }
+
+    fn send_to<R: FromScVal>(&mut self, contract: ScAddress, call: Call<R>) -> Result<R, String> {
+        let result = self.submit(invoke(contract, call)?)?;
+        R::from_sc_val(result)
+    }
+
+    // Simulates the call for its footprint, fee and authorizations, then signs,
+    // sends and waits for it. Returns the invocation's return value.
+    fn submit(&mut self, host_function: HostFunction) -> Result<ScVal, String> {
+        let (_, simulation) = self.simulate(&host_function)?;
+        let auth = simulation.results[0]
+            .auth
+            .iter()
+            .map(|entry| from_base64::<SorobanAuthorizationEntry>(entry))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Only the source account signs, so it must be the one authorizing.
+        for entry in &auth {
+            if let SorobanCredentials::Address(credentials) = &entry.credentials {
+                return Err(format!(
+                    "the call needs authorization from {}, not the source account",
+                    credentials.address
+                ));
+            }
+        }
+        let data: SorobanTransactionData = from_base64(&simulation.transaction_data)?;
+        let resource_fee: u32 = simulation
+            .min_resource_fee
+            .parse()
+            .map_err(|_| "simulation returned no resource fee".to_string())?;
+
+        let sequence = self.sequence()?;
+        let transaction = self.transaction(
+            sequence,
+            host_function,
+            auth,
+            BASE_FEE.saturating_add(resource_fee),
+            TransactionExt::V1(data),
+        )?;
+        let envelope = self.sign(transaction)?;
+        let submission: Submission = self.call(
+            "sendTransaction",
+            json!({ "transaction": to_base64(&envelope)? }),
+        )?;
+        if submission.status != "PENDING" {
+            let reason = match submission.error_result_xdr.as_deref().map(from_base64) {
+                Some(Ok(TransactionResult { result, .. })) => result.name().to_string(),
+                _ => submission.status.to_lowercase(),
+            };
+            return Err(format!("transaction rejected: {reason}"));
+        }
+
+        for _ in 0..POLL_ATTEMPTS {
+            let status: TransactionStatus =
+                self.call("getTransaction", json!({ "hash": submission.hash }))?;
+            match status.status.as_str() {
+                "SUCCESS" => return return_value(status.result_meta_xdr.as_deref()),
+                "NOT_FOUND" => thread::sleep(Duration::from_secs(1)),
+                other => return Err(format!("transaction {}: {other}", submission.hash)),
+            }
+        }
+        Err(format!(
+            "transaction {} was not included in time",
+            submission.hash
+        ))
+    }
+
+    fn sign(&self, transaction: Transaction) -> Result<TransactionEnvelope, String> {
+        let network_id: [u8; 32] = Sha256::digest(self.config.network.passphrase.as_bytes()).into();
+        let hash = transaction.hash(network_id).map_err(xdr_error)?;
+        let public_key = self.key.verifying_key().to_bytes();
+        let signature = DecoratedSignature {
+            hint: SignatureHint(public_key[28..].try_into().unwrap()),
+            signature: Signature(
+                self.key
+                    .sign(&hash)
+                    .to_bytes()
+                    .to_vec()
+                    .try_into()
+                    .map_err(xdr_error)?,
+            ),
+        };
+        Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: transaction,
+            signatures: vec![signature].try_into().map_err(xdr_error)?,
+        }))
+    }
+
+    // Uploads the wasm, then creates the instance with the constructor
+    // arguments in a second transaction, so it is initialized as it is created.
+    pub fn deploy(&mut self, metadata: &Metadata) -> Result<String, String> {
+        let path = &self.config.contract.wasm;
+        let wasm = fs::read(path).map_err(|err| format!("cannot read {path}: {err}"))?;
+        let uploaded = self.submit(HostFunction::UploadContractWasm(
+            wasm.try_into().map_err(xdr_error)?,
+        ))?;
+        let wasm_hash: [u8; 32] = match uploaded {
+            ScVal::Bytes(bytes) => bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| "the upload returned a malformed hash".to_string())?,
+            other => return Err(format!("the upload returned {other:?}")),
+        };
+
+        // The source account's sequence number never repeats, so every
+        // deploy gets a fresh address.
+        let sequence = self.sequence()?;
+        let salt: [u8; 32] = Sha256::new()
+            .chain_update(wasm_hash)
+            .chain_update(sequence.to_be_bytes())
+            .finalize()
+            .into();
+        let constructor = bindings::__constructor(
+            address(&metadata.admin)?,
+            address(&self.config.contract.underlying)?,
+            metadata.name.clone(),
+            metadata.symbol.clone(),
+            metadata.decimals,
+        );
+        let created = self.submit(HostFunction::CreateContractV2(CreateContractArgsV2 {
+            contract_id_preimage: ContractIdPreimage::Address(ContractIdPreimageFromAddress {
+                address: ScAddress::Account(self.source()),
+                salt: Uint256(salt),
+            }),
+            executable: ContractExecutable::Wasm(Hash(wasm_hash)),
+            constructor_args: constructor.args.try_into().map_err(xdr_error)?,
+        }))?;
+        Ok(ScAddress::from_sc_val(created)?.to_string())
+    }
+
+    // With `approve`, first lets the token pull `amount` of the underlying
+    // from `to`.
+    pub fn mint(&mut self, to: &str, amount: i128, approve: bool) -> Result<(), String> {
+        if approve {
+            let live_until = self.latest_ledger()? + APPROVAL_LEDGERS;
+            let underlying = address(&self.config.contract.underlying)?;
+            let call = bindings::approve(address(to)?, self.token()?, amount, live_until);
+            self.send_to(underlying, call)?;
+        }
+        self.send(bindings::mint(address(to)?, amount))
+    }
+
+    pub fn burn(&mut self, from: &str, amount: i128) -> Result<(), String> {
+        self.send(bindings::burn(address(from)?, amount))
+    }
+
+    pub fn balance(&mut self, account: &str) -> Result<i128, String> {
+        self.view(bindings::balance(address(account)?))
+    }
+
+    pub fn rate(&mut self) -> Result<Option<i128>, String> {
+        self.view(bindings::rate_at(u32::MAX))
+    }
+
+    pub fn apy(&mut self, window_ledgers: u32) -> Result<i128, String> {
+        self.view(bindings::apy(window_ledgers))
+    }
+
+    pub fn harvest(&mut self) -> Result<(), String> {
+        self.send(bindings::sync())
+    }
+
+    pub fn admin(&mut self, action: &AdminAction) -> Result<(), String> {
+        let caller = address(&self.caller())?;
+        match action {
+            AdminAction::SetBufferTarget { bps } => self.send(bindings::set_buffer_target(*bps)),
+            AdminAction::SetDripPeriod { seconds } => {
+                self.send(bindings::set_drip_period(*seconds))
+            }
+            AdminAction::SetYieldVault { vault } => {
+                let vault = vault.as_deref().map(address).transpose()?;
+                self.send(bindings::set_yield_vault(vault))
+            }
+            AdminAction::Recall { amount } => self.send(bindings::recall(*amount)),
+            AdminAction::Freeze { account } => {
+                self.send(bindings::freeze(caller, address(account)?))
+            }
+            AdminAction::Unfreeze { account } => {
+                self.send(bindings::unfreeze(caller, address(account)?))
+            }
+            AdminAction::GrantRole { account, role } => self.send(bindings::grant_role(
+                caller,
+                address(account)?,
+                symbol(role)?,
+            )),
+            AdminAction::SetTimelock { delay, grace } => {
+                self.send(bindings::set_timelock(bindings::TimelockConfig {
+                    delay: *delay,
+                    grace: *grace,
+                }))
+            }
+        }
+    }
+
+    pub fn schedule(&mut self, action: &Timelocked) -> Result<u64, String> {
+        self.send(bindings::schedule(operation(action)?))
+    }
+
+    pub fn execute(&mut self, id: u64) -> Result<(), String> {
+        self.send(bindings::execute(id))
+    }
+
+    pub fn dump(&mut self) -> Result<StateDump, String> {
+        Ok(StateDump {
+            name: self.view(bindings::name())?,
+            symbol: self.view(bindings::symbol())?,
+            decimals: self.view(bindings::decimals())?,
+            total_supply: self.view(bindings::total_supply())?,
+            total_assets: self.view(bindings::total_assets())?,
+            rate: self.rate()?,
+            idle_usdc: self.view(bindings::idle_usdc())?,
+            deployed_usdc: self.view(bindings::deployed_usdc())?,
+            buffer_target: self.view(bindings::buffer_target())?,
+            drip_period: self.view(bindings::drip_period())?,
+            queued_shares: self.view(bindings::queued_shares())?,
+            reserved_usdc: self.view(bindings::reserved_usdc())?,
+            checkpoint_count: self.view(bindings::checkpoint_count())?,
+        })
+    }
+}
+
+fn operation(action: &Timelocked) -> Result<bindings::Operation, String> {
+    use bindings::Operation;
+    Ok(match action {
+        Timelocked::SetBufferTarget { bps } => Operation::SetBufferTarget(*bps),
+        Timelocked::SetDripPeriod { seconds } => Operation::SetDripPeriod(*seconds),
+        Timelocked::SetYieldVault { vault } => {
+            Operation::SetYieldVault(vault.as_deref().map(address).transpose()?)
+        }
+        Timelocked::SetFlashFeeBps { bps } => Operation::SetFlashFeeBps(*bps),
+        Timelocked::SetWithdrawalLimit { max_bps, window } => {
+            Operation::SetWithdrawalLimit(bindings::WithdrawalLimit {
+                max_bps: *max_bps,
+                window: *window,
+            })
+        }
+        Timelocked::ClearWithdrawalLimit => Operation::ClearWithdrawalLimit,
+        Timelocked::SetCollateral {
+            asset,
+            max_share_bps,
+            disable,
+        } => Operation::SetCollateral(address(asset)?, !disable, *max_share_bps),
+        Timelocked::SetOracle {
+            oracle,
+            max_age,
+            max_deviation_bps,
+        } => Operation::SetOracle(bindings::OracleConfig {
+            max_age: *max_age,
+            max_deviation_bps: *max_deviation_bps,
+            oracle: address(oracle)?,
+        }),
+        Timelocked::ClearOracle => Operation::ClearOracle,
+        Timelocked::SetTimelock { delay, grace } => {
+            Operation::SetTimelock(bindings::TimelockConfig {
+                delay: *delay,
+                grace: *grace,
+            })
+        }
+    })
+}
+
+fn invoke<R>(contract: ScAddress, call: Call<R>) -> Result<HostFunction, String> {
+    Ok(HostFunction::InvokeContract(InvokeContractArgs {
+        contract_address: contract,
+        function_name: symbol(call.function)?,
+        args: call.args.try_into().map_err(xdr_error)?,
+    }))
+}
+
+fn return_value(meta: Option<&str>) -> Result<ScVal, String> {
+    let meta: TransactionMeta = from_base64(meta.unwrap_or_default())?;
+    let value = match meta {
+        TransactionMeta::V3(meta) => meta.soroban_meta.map(|soroban| soroban.return_value),
+        TransactionMeta::V4(meta) => meta.soroban_meta.and_then(|soroban| soroban.return_value),
+        _ => None,
+    };
+    value.ok_or_else(|| "the transaction meta has no return value".into())
+}
+
+pub fn address(strkey: &str) -> Result<ScAddress, String> {
+    strkey
+        .parse()
+        .map_err(|_| format!("`{strkey}` is not an account or contract address"))
+}
+
+fn symbol(name: &str) -> Result<ScSymbol, String> {
+    ScSymbol::try_from(name).map_err(|_| format!("`{name}` is not a valid symbol"))
+}
+
+fn to_base64<X: WriteXdr>(value: &X) -> Result<String, String> {
+    value.to_xdr_base64(Limits::none()).map_err(xdr_error)
+}
+
+fn from_base64<X: ReadXdr>(value: &str) -> Result<X, String> {
+    X::from_xdr_base64(value, Limits::none()).map_err(xdr_error)
+}
+
+fn xdr_error(err: stellar_xdr::curr::Error) -> String {
+    format!("xdr: {err}")
+}
+
+// `source` is a secret key or the name of a `stellar keys` identity.
+fn signing_key(source: &str) -> Result<SigningKey, String> {
+    let secret = if source.starts_with('S') && source.len() == 56 {
+        source.to_string()
+    } else {
+        identity_secret(source)?
+    };
+    let key = stellar_strkey::ed25519::PrivateKey::from_string(&secret)
+        .map_err(|_| format!("the secret key for `{source}` is invalid"))?;
+    Ok(SigningKey::from_bytes(&key.0))
+}
+
+fn identity_secret(name: &str) -> Result<String, String> {
+    #[derive(Deserialize)]
+    struct Identity {
+        secret_key: Option<String>,
+    }
+
+    let global = std::env::var("STELLAR_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|home| PathBuf::from(home).join(".config/stellar")));
+    let file = format!("identity/{name}.toml");
+    let path = std::iter::once(PathBuf::from(".stellar").join(&file))
+        .chain(global.ok().map(|global| global.join(&file)))
+        .find(|path| path.is_file())
+        .ok_or_else(|| format!("no secret key or `stellar keys` identity named `{name}`"))?;
+    let input = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    let identity: Identity = toml::from_str(&input).map_err(|err| err.to_string())?;
+    identity.secret_key.ok_or_else(|| {
+        format!(
+            "identity `{name}` has no secret_key (seed phrases are not supported); \
+             set account.source to its secret key"
+        )
+    })
+}
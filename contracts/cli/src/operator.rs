@@ -0,0 +1,136 @@
+use std::fmt;
+
+use clap::Subcommand;
+
+pub const RATE_SCALE: i128 = 1_000_000_000_000;
+
+pub struct Metadata {
+    pub admin: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Subcommand)]
+pub enum AdminAction {
+    /// Share of total assets kept idle, in bps
+    SetBufferTarget {
+        bps: u32,
+    },
+    /// Vesting period for recognized yield, in seconds
+    SetDripPeriod {
+        seconds: u64,
+    },
+    /// Yield vault contract; omit to clear
+    SetYieldVault {
+        vault: Option<String>,
+    },
+    /// Pull USDC back from the yield vault
+    Recall {
+        amount: i128,
+    },
+    Freeze {
+        account: String,
+    },
+    Unfreeze {
+        account: String,
+    },
+    GrantRole {
+        account: String,
+        role: String,
+    },
+    /// Configure the timelock; once set, it can only change through `schedule`
+    SetTimelock {
+        /// Ledgers between scheduling and execution
+        delay: u32,
+        /// Ledgers an operation stays executable after its delay
+        grace: u32,
+    },
+}
+
+// Admin changes that go through the timelock with `schedule` and `execute`.
+#[derive(Clone, Debug, PartialEq, Subcommand)]
+pub enum Timelocked {
+    SetBufferTarget {
+        bps: u32,
+    },
+    SetDripPeriod {
+        seconds: u64,
+    },
+    /// Yield vault contract; omit to clear
+    SetYieldVault {
+        vault: Option<String>,
+    },
+    SetFlashFeeBps {
+        bps: u32,
+    },
+    SetWithdrawalLimit {
+        max_bps: u32,
+        /// Window length in seconds
+        window: u64,
+    },
+    ClearWithdrawalLimit,
+    SetCollateral {
+        asset: String,
+        max_share_bps: u32,
+        #[arg(long)]
+        disable: bool,
+    },
+    SetOracle {
+        oracle: String,
+        /// Oldest accepted price, in seconds
+        max_age: u64,
+        max_deviation_bps: u32,
+    },
+    ClearOracle,
+    SetTimelock {
+        delay: u32,
+        grace: u32,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateDump {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+    pub total_supply: i128,
+    pub total_assets: i128,
+    pub rate: Option<i128>,
+    pub idle_usdc: i128,
+    pub deployed_usdc: i128,
+    pub buffer_target: u32,
+    pub drip_period: u64,
+    pub queued_shares: i128,
+    pub reserved_usdc: i128,
+    pub checkpoint_count: u32,
+}
+
+impl fmt::Display for StateDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "name = {:?}", self.name)?;
+        writeln!(f, "symbol = {:?}", self.symbol)?;
+        writeln!(f, "decimals = {}", self.decimals)?;
+        writeln!(f, "total_supply = {}", self.total_supply)?;
+        writeln!(f, "total_assets = {}", self.total_assets)?;
+        match self.rate {
+            Some(rate) => writeln!(f, "rate = {rate}")?,
+            None => writeln!(f, "# rate = (no checkpoint yet)")?,
+        }
+        writeln!(f, "idle_usdc = {}", self.idle_usdc)?;
+        writeln!(f, "deployed_usdc = {}", self.deployed_usdc)?;
+        writeln!(f, "buffer_target = {}", self.buffer_target)?;
+        writeln!(f, "drip_period = {}", self.drip_period)?;
+        writeln!(f, "queued_shares = {}", self.queued_shares)?;
+        writeln!(f, "reserved_usdc = {}", self.reserved_usdc)?;
+        writeln!(f, "checkpoint_count = {}", self.checkpoint_count)
+    }
+}
+
+pub fn format_rate(rate: i128) -> String {
+    format!(
+        "{}.{:012}",
+        rate / RATE_SCALE,
+        (rate % RATE_SCALE).unsigned_abs()
+    )
+}
@@ -0,0 +1,281 @@
+#![cfg(test)]
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    panic::AssertUnwindSafe,
+    rc::Rc,
+};
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use rusd_rebasing_token::testutils::{UsdcMock, UsdcMockClient};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use soroban_sdk::{
+    testutils::Ledger as _,
+    xdr::{
+        AccountEntry, ContractExecutable, ContractIdPreimage, HostFunction, LedgerEntryData,
+        LedgerKey, Limits, MuxedAccount, OperationBody, ReadXdr, ScVal, SequenceNumber,
+        SorobanResources, SorobanTransactionData, SorobanTransactionMetaV2, Thresholds,
+        TransactionEnvelope, TransactionMeta, TransactionMetaV4, TransactionResult,
+        TransactionResultResult, Uint256, WriteXdr,
+    },
+    Address, Bytes, BytesN, Env, Symbol, TryFromVal, Val, Vec as SorobanVec,
+};
+
+use crate::network::Transport;
+
+// Answers the RPC calls the CLI makes from a local `Env`, so the generated
+// bindings, signing and submission are exercised end to end without a
+// network. Simulation applies the call and sending only checks the signed
+// transaction against it; authorization is mocked, so simulations return
+// no auth entries.
+#[derive(Clone)]
+pub struct Sandbox {
+    pub env: Env,
+    usdc: Address,
+    passphrase: String,
+    simulated: Rc<RefCell<Option<(HostFunction, ScVal)>>>,
+    results: Rc<RefCell<HashMap<String, ScVal>>>,
+    sequence: Rc<Cell<i64>>,
+}
+
+impl Sandbox {
+    pub fn new(decimals: u32, passphrase: &str) -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        // Uploading and instantiating the real wasm exceeds the default budget.
+        env.cost_estimate().budget().reset_unlimited();
+        let usdc = env.register(UsdcMock, ());
+        UsdcMockClient::new(&env, &usdc).set_decimals(&decimals);
+        Sandbox {
+            env,
+            usdc,
+            passphrase: passphrase.into(),
+            simulated: Default::default(),
+            results: Default::default(),
+            sequence: Default::default(),
+        }
+    }
+
+    pub fn usdc(&self) -> UsdcMockClient<'_> {
+        UsdcMockClient::new(&self.env, &self.usdc)
+    }
+
+    pub fn address(&self, strkey: &str) -> Address {
+        Address::from_str(&self.env, strkey)
+    }
+
+    pub fn advance_ledgers(&self, ledgers: u32) {
+        self.env
+            .ledger()
+            .with_mut(|ledger| ledger.sequence_number += ledgers);
+    }
+
+    fn account(&self, key: LedgerKey) -> Value {
+        let LedgerKey::Account(key) = key else {
+            return json!({ "entries": [] });
+        };
+        let entry = LedgerEntryData::Account(AccountEntry {
+            account_id: key.account_id,
+            balance: 10_000_000_000,
+            seq_num: SequenceNumber(self.sequence.get()),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: Default::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: Default::default(),
+            ext: Default::default(),
+        });
+        json!({ "entries": [{ "xdr": to_base64(&entry) }] })
+    }
+
+    fn simulate(&self, envelope: TransactionEnvelope) -> Value {
+        let host_function = invocation(&envelope);
+        match self.apply(&host_function) {
+            Ok(result) => {
+                let data = SorobanTransactionData {
+                    ext: Default::default(),
+                    resources: SorobanResources {
+                        footprint: Default::default(),
+                        instructions: 1_000_000,
+                        disk_read_bytes: 0,
+                        write_bytes: 0,
+                    },
+                    resource_fee: 10_000,
+                };
+                let response = json!({
+                    "transactionData": to_base64(&data),
+                    "minResourceFee": "10000",
+                    "results": [{ "xdr": to_base64(&result), "auth": [] }],
+                });
+                *self.simulated.borrow_mut() = Some((host_function, result));
+                response
+            }
+            Err(err) => json!({ "error": err }),
+        }
+    }
+
+    fn apply(&self, host_function: &HostFunction) -> Result<ScVal, String> {
+        let env = &self.env;
+        match host_function.clone() {
+            HostFunction::InvokeContract(args) => {
+                let contract = Address::try_from_val(env, &ScVal::Address(args.contract_address))
+                    .map_err(|err| format!("{err:?}"))?;
+                let function = Symbol::new(env, &args.function_name.to_utf8_string_lossy());
+                let args = from_sc_vals(env, args.args.to_vec())?;
+                match env.try_invoke_contract::<Val, soroban_sdk::Error>(&contract, &function, args)
+                {
+                    Ok(Ok(val)) => to_sc_val(env, val),
+                    Ok(Err(err)) => Err(format!("{err:?}")),
+                    Err(Ok(err)) => Err(format!("{err:?}")),
+                    Err(Err(err)) => Err(format!("{err:?}")),
+                }
+            }
+            HostFunction::UploadContractWasm(wasm) => {
+                let wasm = Bytes::from_slice(env, &wasm);
+                let hash = env.deployer().upload_contract_wasm(wasm);
+                Ok(ScVal::Bytes(hash.to_array().to_vec().try_into().unwrap()))
+            }
+            // `deploy_v2` panics when the constructor fails, so the failure
+            // is caught and reported like any other contract error.
+            HostFunction::CreateContractV2(args) => {
+                let ContractIdPreimage::Address(preimage) = args.contract_id_preimage else {
+                    return Err("only address preimages are supported".into());
+                };
+                let deployer = Address::try_from_val(env, &ScVal::Address(preimage.address))
+                    .map_err(|err| format!("{err:?}"))?;
+                let salt = BytesN::from_array(env, &preimage.salt.0);
+                let ContractExecutable::Wasm(hash) = args.executable else {
+                    return Err("only wasm executables are supported".into());
+                };
+                let hash = BytesN::from_array(env, &hash.0);
+                let args = from_sc_vals(env, args.constructor_args.to_vec())?;
+                let deployed = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    env.deployer()
+                        .with_address(deployer, salt)
+                        .deploy_v2(hash, args)
+                }))
+                .map_err(|panic| panic_message(&*panic))?;
+                Ok(ScVal::from(&deployed))
+            }
+            other => Err(format!("unsupported host function {}", other.name())),
+        }
+    }
+
+    fn send(&self, envelope: TransactionEnvelope) -> Value {
+        let TransactionEnvelope::Tx(signed) = &envelope else {
+            return rejected("", TransactionResultResult::TxMalformed);
+        };
+        let network_id: [u8; 32] = Sha256::digest(self.passphrase.as_bytes()).into();
+        let digest = signed.tx.hash(network_id).unwrap();
+        let hash = hex(&digest);
+        let MuxedAccount::Ed25519(Uint256(public_key)) = &signed.tx.source_account else {
+            return rejected(&hash, TransactionResultResult::TxMalformed);
+        };
+        let key = VerifyingKey::from_bytes(public_key).unwrap();
+        let signed_by_source = signed.signatures.iter().any(|decorated| {
+            Signature::from_slice(&decorated.signature)
+                .is_ok_and(|signature| key.verify(&digest, &signature).is_ok())
+        });
+        if !signed_by_source {
+            return rejected(&hash, TransactionResultResult::TxBadAuth);
+        }
+        if signed.tx.seq_num.0 != self.sequence.get() + 1 {
+            return rejected(&hash, TransactionResultResult::TxBadSeq);
+        }
+        let host_function = invocation(&envelope);
+        let result = match self.simulated.borrow_mut().take() {
+            Some((simulated, result)) if simulated == host_function => result,
+            _ => return rejected(&hash, TransactionResultResult::TxMalformed),
+        };
+        self.sequence.set(signed.tx.seq_num.0);
+        self.results.borrow_mut().insert(hash.clone(), result);
+        json!({ "status": "PENDING", "hash": hash })
+    }
+
+    fn transaction(&self, hash: &str) -> Value {
+        let Some(result) = self.results.borrow().get(hash).cloned() else {
+            return json!({ "status": "NOT_FOUND" });
+        };
+        let meta = TransactionMeta::V4(TransactionMetaV4 {
+            ext: Default::default(),
+            tx_changes_before: Default::default(),
+            operations: Default::default(),
+            tx_changes_after: Default::default(),
+            soroban_meta: Some(SorobanTransactionMetaV2 {
+                ext: Default::default(),
+                return_value: Some(result),
+            }),
+            events: Default::default(),
+            diagnostic_events: Default::default(),
+        });
+        json!({ "status": "SUCCESS", "resultMetaXdr": to_base64(&meta) })
+    }
+}
+
+impl Transport for Sandbox {
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let xdr = |field: &str| params[field].as_str().unwrap_or_default().to_string();
+        Ok(match method {
+            "getLatestLedger" => json!({ "sequence": self.env.ledger().sequence() }),
+            "getLedgerEntries" => self.account(from_base64(params["keys"][0].as_str().unwrap())),
+            "simulateTransaction" => self.simulate(from_base64(&xdr("transaction"))),
+            "sendTransaction" => self.send(from_base64(&xdr("transaction"))),
+            "getTransaction" => self.transaction(&xdr("hash")),
+            other => return Err(format!("method `{other}` is not supported")),
+        })
+    }
+}
+
+fn invocation(envelope: &TransactionEnvelope) -> HostFunction {
+    let TransactionEnvelope::Tx(envelope) = envelope else {
+        panic!("only v1 envelopes are supported");
+    };
+    let OperationBody::InvokeHostFunction(op) = &envelope.tx.operations[0].body else {
+        panic!("only host function invocations are supported");
+    };
+    op.host_function.clone()
+}
+
+fn rejected(hash: &str, result: TransactionResultResult) -> Value {
+    let result = TransactionResult {
+        fee_charged: 0,
+        result,
+        ext: Default::default(),
+    };
+    json!({ "status": "ERROR", "hash": hash, "errorResultXdr": to_base64(&result) })
+}
+
+fn from_sc_vals(env: &Env, values: Vec<ScVal>) -> Result<SorobanVec<Val>, String> {
+    let mut vals = SorobanVec::new(env);
+    for value in values {
+        vals.push_back(Val::try_from_val(env, &value).map_err(|err| format!("{err:?}"))?);
+    }
+    Ok(vals)
+}
+
+fn to_sc_val(env: &Env, val: Val) -> Result<ScVal, String> {
+    ScVal::try_from_val(env, &val).map_err(|err| format!("{err:?}"))
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    match (panic.downcast_ref::<String>(), panic.downcast_ref::<&str>()) {
+        (Some(message), _) => message.clone(),
+        (None, Some(message)) => message.to_string(),
+        (None, None) => "deployment panicked".into(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn to_base64<X: WriteXdr>(value: &X) -> String {
+    value.to_xdr_base64(Limits::none()).unwrap()
+}
+
+fn from_base64<X: ReadXdr>(value: &str) -> X {
+    X::from_xdr_base64(value, Limits::none()).unwrap()
+}
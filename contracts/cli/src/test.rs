@@ -0,0 +1,251 @@
+use std::fs;
+
+use ed25519_dalek::SigningKey;
+use rusd_rebasing_token::{oracle, timelock};
+use soroban_sdk::{
+    testutils::Address as _,
+    xdr::{ScAddress, ScVal},
+    Address, Env, IntoVal, TryFromVal, Val,
+};
+
+use crate::{
+    bindings::{self, FromScVal, ToScVal},
+    config::{store_contract_id, Config},
+    network::Network,
+    operator::{format_rate, AdminAction, Timelocked},
+    run,
+    sandbox::Sandbox,
+    ContractCommand,
+};
+
+const CONFIG: &str = r#"
+[network]
+rpc_url = "http://localhost:8000/soroban/rpc"
+passphrase = "Standalone Network ; February 2017"
+
+[account]
+source = "alice"
+
+[contract]
+wasm = "target/wasm32v1-none/release/rusd_rebasing_token.wasm"
+underlying = "CUSDC"
+name = "rUSD"
+symbol = "rUSD"
+decimals = 7
+id = "CRUSD"
+"#;
+
+fn config() -> Config {
+    Config::parse(CONFIG).unwrap()
+}
+
+fn secret(seed: u8) -> String {
+    stellar_strkey::ed25519::PrivateKey(SigningKey::from_bytes(&[seed; 32]).to_bytes()).to_string()
+}
+
+// A fresh sandbox, configured to deploy the built token wasm over the
+// sandbox's underlying.
+fn sandbox(decimals: u32) -> (Sandbox, Config) {
+    let mut config = config();
+    let sandbox = Sandbox::new(decimals, &config.network.passphrase);
+    config.account.source = secret(1);
+    config.contract.wasm = rusd_token_wasm::PATH.into();
+    config.contract.underlying = sandbox.usdc().address.to_string().to_string();
+    config.contract.id = None;
+    (sandbox, config)
+}
+
+fn network(sandbox: &Sandbox, config: &Config) -> Network<Sandbox> {
+    Network::new(config.clone(), sandbox.clone()).unwrap()
+}
+
+#[test]
+fn config_parses_and_stores_contract_id() {
+    let config = config();
+    assert_eq!(
+        config.network.passphrase,
+        "Standalone Network ; February 2017"
+    );
+    assert_eq!(config.contract.admin, None);
+    assert_eq!(config.contract_id(), Ok("CRUSD"));
+
+    let path = std::env::temp_dir().join(format!("rusd-cli-{}.toml", std::process::id()));
+    fs::write(&path, include_str!("../rusd.example.toml")).unwrap();
+    assert!(Config::load(&path).unwrap().contract_id().is_err());
+    store_contract_id(&path, "CNEW").unwrap();
+    let stored = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(stored.starts_with("# Copy to rusd.toml"));
+    assert_eq!(Config::parse(&stored).unwrap().contract_id(), Ok("CNEW"));
+}
+
+#[test]
+fn bindings_match_the_contract_encoding() {
+    let env = Env::default();
+    let oracle = Address::generate(&env);
+    let address: ScAddress = oracle.to_string().to_string().parse().unwrap();
+    let cases = [
+        (
+            timelock::Operation::SetTimelock(timelock::TimelockConfig {
+                delay: 10,
+                grace: 20,
+            }),
+            bindings::Operation::SetTimelock(bindings::TimelockConfig {
+                delay: 10,
+                grace: 20,
+            }),
+        ),
+        (
+            timelock::Operation::SetYieldVault(None),
+            bindings::Operation::SetYieldVault(None),
+        ),
+        (
+            timelock::Operation::SetCollateral(oracle.clone(), true, 5_000),
+            bindings::Operation::SetCollateral(address.clone(), true, 5_000),
+        ),
+        (
+            timelock::Operation::SetOracle(oracle::OracleConfig {
+                oracle: oracle.clone(),
+                max_age: 60,
+                max_deviation_bps: 100,
+            }),
+            bindings::Operation::SetOracle(bindings::OracleConfig {
+                oracle: address,
+                max_age: 60,
+                max_deviation_bps: 100,
+            }),
+        ),
+        (
+            timelock::Operation::ClearOracle,
+            bindings::Operation::ClearOracle,
+        ),
+        (
+            timelock::Operation::SetDripPeriod(3_600),
+            bindings::Operation::SetDripPeriod(3_600),
+        ),
+    ];
+    for (contract, binding) in cases {
+        let val: Val = contract.into_val(&env);
+        let expected = ScVal::try_from_val(&env, &val).unwrap();
+        assert_eq!(binding.to_sc_val(), expected);
+        assert_eq!(bindings::Operation::from_sc_val(expected), Ok(binding));
+    }
+    assert!(bindings::TimelockConfig::from_sc_val(ScVal::Void).is_err());
+}
+
+#[test]
+fn sandbox_runs_the_operator_flow() {
+    let (sandbox, mut config) = sandbox(7);
+    let mut rusd = network(&sandbox, &config);
+    let alice = rusd.caller();
+
+    let balance = ContractCommand::Balance { account: None };
+    let err = run(&balance, &config, &mut rusd).unwrap_err();
+    assert!(err.contains("rusd-cli deploy"), "{err}");
+    let id = run(&ContractCommand::Deploy, &config, &mut rusd).unwrap();
+    assert!(id.starts_with('C'), "{id}");
+    config.contract.id = Some(id.clone());
+    let mut rusd = network(&sandbox, &config);
+    let token = sandbox.address(&id);
+
+    sandbox.usdc().mint(&sandbox.address(&alice), &1_000);
+    let mint = |approve, amount| ContractCommand::Mint {
+        to: None,
+        approve,
+        amount,
+    };
+    assert!(run(&mint(false, 1_000), &config, &mut rusd).is_err());
+    assert_eq!(
+        run(&mint(true, 1_000), &config, &mut rusd),
+        Ok("1000".into())
+    );
+    assert_eq!(
+        run(&ContractCommand::Rate { window: None }, &config, &mut rusd),
+        Ok(format_rate(1_000_000_000_000))
+    );
+
+    sandbox.usdc().mint(&token, &100);
+    assert_eq!(
+        run(&ContractCommand::Harvest, &config, &mut rusd),
+        Ok("synced".into())
+    );
+    assert_eq!(run(&balance, &config, &mut rusd), Ok("1100".into()));
+
+    let burn = ContractCommand::Burn {
+        from: None,
+        amount: 100,
+    };
+    assert_eq!(run(&burn, &config, &mut rusd), Ok("1000".into()));
+    assert_eq!(sandbox.usdc().balance(&sandbox.address(&alice)), 100);
+
+    let admin = |action| ContractCommand::Admin(action);
+    for action in [
+        AdminAction::SetBufferTarget { bps: 2_500 },
+        AdminAction::SetDripPeriod { seconds: 3_600 },
+        AdminAction::GrantRole {
+            account: alice.clone(),
+            role: "compliance".into(),
+        },
+        AdminAction::Freeze {
+            account: alice.clone(),
+        },
+    ] {
+        assert_eq!(run(&admin(action), &config, &mut rusd), Ok("done".into()));
+    }
+    assert!(run(&mint(true, 10), &config, &mut rusd).is_err());
+    let unfreeze = admin(AdminAction::Unfreeze {
+        account: alice.clone(),
+    });
+    assert_eq!(run(&unfreeze, &config, &mut rusd), Ok("done".into()));
+    assert_eq!(run(&mint(true, 10), &config, &mut rusd), Ok("1010".into()));
+
+    // Once the timelock is set, admin changes only go through `schedule`.
+    let set_timelock = admin(AdminAction::SetTimelock {
+        delay: 10,
+        grace: 100,
+    });
+    assert_eq!(run(&set_timelock, &config, &mut rusd), Ok("done".into()));
+    let set_buffer = admin(AdminAction::SetBufferTarget { bps: 1_000 });
+    let err = run(&set_buffer, &config, &mut rusd).unwrap_err();
+    assert!(err.contains("Contract, #29"), "{err}");
+
+    let schedule = ContractCommand::Schedule(Timelocked::SetBufferTarget { bps: 1_000 });
+    let id: u64 = run(&schedule, &config, &mut rusd).unwrap().parse().unwrap();
+    let execute = ContractCommand::Execute { id };
+    assert!(run(&execute, &config, &mut rusd).is_err());
+    sandbox.advance_ledgers(10);
+    assert_eq!(run(&execute, &config, &mut rusd), Ok("executed".into()));
+
+    let dump = rusd.dump().unwrap();
+    assert_eq!(dump.name, "rUSD");
+    assert_eq!(dump.decimals, 7);
+    assert_eq!(dump.total_supply, 1_010);
+    assert_eq!(dump.buffer_target, 1_000);
+    assert_eq!(dump.drip_period, 3_600);
+    let printed = run(&ContractCommand::Dump, &config, &mut rusd).unwrap();
+    assert!(printed.contains("total_supply = 1010"));
+    assert!(printed.contains("buffer_target = 1000"));
+}
+
+#[test]
+fn sandbox_rejects_decimals_mismatch() {
+    let (sandbox, config) = sandbox(6);
+    let mut rusd = network(&sandbox, &config);
+    let err = run(&ContractCommand::Deploy, &config, &mut rusd).unwrap_err();
+    assert!(err.contains("Contract, #36"), "{err}");
+}
+
+#[test]
+fn sandbox_rejects_transactions_signed_for_another_network() {
+    let (sandbox, mut config) = sandbox(7);
+    config.network.passphrase = "Test SDF Network ; September 2015".into();
+    let mut rusd = network(&sandbox, &config);
+    assert_eq!(
+        run(&ContractCommand::Deploy, &config, &mut rusd),
+        Err("transaction rejected: TxBadAuth".into())
+    );
+
+    config.account.source = "no-such-identity".into();
+    let err = Network::new(config, sandbox).err().unwrap();
+    assert!(err.contains("no-such-identity"), "{err}");
+}
@@ -0,0 +1,130 @@
+#![no_std]
+
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype,
+    panic_with_error, xdr::ToXdr, Address, BytesN, Env, String, Vec,
+};
+use stellar_access::access_control::{self as access_control, AccessControl};
+use stellar_macros::{default_impl, only_admin};
+
+#[contracterror]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    NotInitialized = 2,
+    AlreadyDeployed = 3,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    WasmHash,
+    Tokens,
+    Token(Address),
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenDeployed {
+    #[topic]
+    pub underlying: Address,
+    pub token: Address,
+    pub admin: Address,
+}
+
+#[contract]
+pub struct RUsdFactory;
+
+#[contractimpl]
+impl RUsdFactory {
+    // Runs as part of deployment, so nobody can claim the admin role between
+    // deploying the factory and configuring it.
+    pub fn __constructor(env: Env, admin: Address, wasm_hash: BytesN<32>) {
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::WasmHash, &wasm_hash);
+        access_control::set_admin(&env, &admin);
+    }
+
+    #[only_admin]
+    pub fn set_wasm_hash(env: Env, wasm_hash: BytesN<32>) {
+        env.storage().instance().set(&DataKey::WasmHash, &wasm_hash);
+    }
+
+    pub fn wasm_hash(env: Env) -> BytesN<32> {
+        read_wasm_hash(&env)
+    }
+
+    #[only_admin]
+    pub fn deploy(
+        env: Env,
+        underlying: Address,
+        admin: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
+    ) -> Address {
+        admin.require_auth();
+        let key = DataKey::Token(underlying.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(&env, Error::AlreadyDeployed);
+        }
+        let wasm_hash = read_wasm_hash(&env);
+        let token = env
+            .deployer()
+            .with_current_contract(salt_for(&env, &underlying))
+            .deploy_v2(
+                wasm_hash,
+                (admin.clone(), underlying.clone(), name, symbol, decimals),
+            );
+
+        env.storage().persistent().set(&key, &token);
+        let mut tokens = read_tokens(&env);
+        tokens.push_back(token.clone());
+        env.storage().instance().set(&DataKey::Tokens, &tokens);
+        TokenDeployed {
+            underlying,
+            token: token.clone(),
+            admin,
+        }
+        .publish(&env);
+        token
+    }
+
+    pub fn token(env: Env, underlying: Address) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Token(underlying))
+    }
+
+    pub fn tokens(env: Env) -> Vec<Address> {
+        read_tokens(&env)
+    }
+
+    pub fn deployed_address(env: Env, underlying: Address) -> Address {
+        env.deployer()
+            .with_current_contract(salt_for(&env, &underlying))
+            .deployed_address()
+    }
+}
+
+#[default_impl]
+#[contractimpl]
+impl AccessControl for RUsdFactory {}
+
+fn read_wasm_hash(env: &Env) -> BytesN<32> {
+    env.storage()
+        .instance()
+        .get(&DataKey::WasmHash)
+        .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+}
+
+fn read_tokens(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Tokens)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn salt_for(env: &Env, underlying: &Address) -> BytesN<32> {
+    env.crypto().sha256(&underlying.clone().to_xdr(env)).into()
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,150 @@
+#![cfg(test)]
+
+extern crate std;
+
+use rusd_rebasing_token::RUsdTokenClient;
+use soroban_sdk::{
+    testutils::Address as AddressTest,
+    token::StellarAssetClient,
+    vec,
+    xdr::{ScErrorCode, ScErrorType},
+    Address, BytesN, Env, Error as SorobanError, IntoVal, String, Symbol,
+};
+
+use crate::{Error, RUsdFactory, RUsdFactoryClient};
+
+fn upload_token_wasm(env: &Env) -> BytesN<32> {
+    env.deployer()
+        .upload_contract_wasm(soroban_sdk::Bytes::from_slice(env, rusd_token_wasm::WASM))
+}
+
+fn setup<'a>() -> (Env, RUsdFactoryClient<'a>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+    let admin = Address::generate(&env);
+    let wasm_hash = upload_token_wasm(&env);
+    let factory = RUsdFactoryClient::new(
+        &env,
+        &env.register(RUsdFactory, (admin.clone(), wasm_hash)),
+    );
+    (env, factory, admin)
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn constructor_requires_admin_auth() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let wasm_hash = upload_token_wasm(&env);
+    env.register(RUsdFactory, (Address::generate(&env), wasm_hash));
+}
+
+fn underlying(env: &Env) -> Address {
+    env.register_stellar_asset_contract_v2(Address::generate(env))
+        .address()
+}
+
+#[test]
+fn deploys_initialized_tokens_per_underlying() {
+    let (env, factory, _) = setup();
+    let token_admin = Address::generate(&env);
+    let usdc = underlying(&env);
+    let eurc = underlying(&env);
+
+    let predicted = factory.deployed_address(&usdc);
+    let r_usd = factory.deploy(
+        &usdc,
+        &token_admin,
+        &String::from_str(&env, "Rebasing USD"),
+        &String::from_str(&env, "rUSD"),
+        &7,
+    );
+    let r_eur = factory.deploy(
+        &eurc,
+        &token_admin,
+        &String::from_str(&env, "Rebasing EUR"),
+        &String::from_str(&env, "rEURC"),
+        &7,
+    );
+    assert_eq!(r_usd, predicted);
+    assert_ne!(r_usd, r_eur);
+    assert_eq!(factory.token(&usdc), Some(r_usd.clone()));
+    assert_eq!(factory.token(&eurc), Some(r_eur.clone()));
+    assert_eq!(factory.token(&underlying(&env)), None);
+    assert_eq!(factory.tokens().len(), 2);
+
+    let token = RUsdTokenClient::new(&env, &r_eur);
+    assert_eq!(token.symbol(), String::from_str(&env, "rEURC"));
+    assert_eq!(token.get_admin(), Some(token_admin));
+    assert_eq!(token.total_supply(), 0);
+
+    let alice = Address::generate(&env);
+    StellarAssetClient::new(&env, &eurc).mint(&alice, &1_000);
+    soroban_sdk::token::Client::new(&env, &eurc).approve(&alice, &r_eur, &1_000, &1_000);
+    token.mint(&alice, &400);
+    assert_eq!(token.balance(&alice), 400);
+    assert_eq!(RUsdTokenClient::new(&env, &r_usd).balance(&alice), 0);
+}
+
+#[test]
+fn rejects_duplicate_underlying() {
+    let (env, factory, admin) = setup();
+    let usdc = underlying(&env);
+    let name = String::from_str(&env, "Rebasing USD");
+    let symbol = String::from_str(&env, "rUSD");
+    factory.deploy(&usdc, &admin, &name, &symbol, &7);
+    assert_eq!(
+        factory.try_deploy(&usdc, &admin, &name, &symbol, &7),
+        Err(Ok(Error::AlreadyDeployed.into()))
+    );
+    // The host reports a failing constructor (here `DecimalsMismatch`) as a
+    // failed deployment rather than passing the contract error through.
+    assert_eq!(
+        factory.try_deploy(&underlying(&env), &admin, &name, &symbol, &6),
+        Err(Ok(SorobanError::from_type_and_code(
+            ScErrorType::Context,
+            ScErrorCode::InvalidAction
+        )))
+    );
+    assert_eq!(factory.tokens().len(), 1);
+}
+
+#[test]
+fn admin_only_configuration() {
+    let (env, factory, admin) = setup();
+    let attacker = Address::generate(&env);
+    let args = vec![
+        &env,
+        attacker.into_val(&env),
+        BytesN::from_array(&env, &[0; 32]).into_val(&env),
+    ];
+    for function in ["init", "__constructor"] {
+        let result = env.try_invoke_contract::<(), Error>(
+            &factory.address,
+            &Symbol::new(&env, function),
+            args.clone(),
+        );
+        assert!(result.is_err());
+    }
+    assert_eq!(factory.get_admin(), Some(admin.clone()));
+    assert_eq!(factory.wasm_hash(), upload_token_wasm(&env));
+
+    env.set_auths(&[]);
+    assert_eq!(
+        factory.try_deploy(
+            &underlying(&env),
+            &admin,
+            &String::from_str(&env, "Rebasing USD"),
+            &String::from_str(&env, "rUSD"),
+            &7,
+        ),
+        Err(Ok(SorobanError::from_type_and_code(
+            ScErrorType::Context,
+            ScErrorCode::InvalidAction
+        )))
+    );
+    assert!(factory
+        .try_set_wasm_hash(&BytesN::from_array(&env, &[0; 32]))
+        .is_err());
+}
@@ -0,0 +1,214 @@
+#![no_main]
+
+use derive_arbitrary::Arbitrary;
+use libfuzzer_sys::{arbitrary, fuzz_target};
+use rusd_rebasing_token::{
+    testutils::{UsdcMock, UsdcMockClient},
+    DataKey, RUsdToken, RUsdTokenClient,
+};
+use soroban_sdk::{testutils::Address as _, Address, Env, String};
+
+const ACTORS: usize = 4;
+const FUNDING: i128 = 1_000_000_000_000;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Mint {
+        to: u8,
+        amount: u32,
+    },
+    Burn {
+        from: u8,
+        amount: u32,
+    },
+    Transfer {
+        from: u8,
+        to: u8,
+        amount: u32,
+    },
+    Approve {
+        owner: u8,
+        spender: u8,
+        amount: u32,
+    },
+    TransferFrom {
+        spender: u8,
+        from: u8,
+        to: u8,
+        amount: u32,
+    },
+    Donate {
+        amount: u32,
+    },
+    Sync,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Ledger {
+    deposited: i128,
+    withdrawn: i128,
+    received: i128,
+    sent: i128,
+    // Recognized yield credited in proportion to the shares held when it
+    // was recognized.
+    earned: i128,
+}
+
+fn shares(env: &Env, rusd_id: &Address, key: DataKey) -> i128 {
+    env.as_contract(rusd_id, || env.storage().instance().get(&key).unwrap_or(0))
+}
+
+fn ceil_div(a: i128, b: i128) -> i128 {
+    (a + b - 1) / b
+}
+
+fuzz_target!(|ops: std::vec::Vec<Op>| {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let usdc_id = env.register(UsdcMock, ());
+    let usdc = UsdcMockClient::new(&env, &usdc_id);
+    let name = String::from_str(&env, "rUSD");
+    let rusd_id = env.register(
+        RUsdToken,
+        (
+            Address::generate(&env),
+            usdc_id.clone(),
+            name.clone(),
+            name,
+            7_u32,
+        ),
+    );
+    let rusd = RUsdTokenClient::new(&env, &rusd_id);
+
+    let actors: std::vec::Vec<Address> = (0..ACTORS).map(|_| Address::generate(&env)).collect();
+    let live_until = env.ledger().sequence() + 10_000;
+    for actor in &actors {
+        usdc.mint(actor, &FUNDING);
+        usdc.approve(actor, &rusd_id, &FUNDING, &live_until);
+    }
+    let donor = Address::generate(&env);
+    usdc.mint(&donor, &FUNDING);
+
+    let mut ledgers = [Ledger::default(); ACTORS];
+    let actor = |index: u8| index as usize % ACTORS;
+
+    for (step, op) in ops.iter().enumerate() {
+        let held_before: std::vec::Vec<i128> = actors
+            .iter()
+            .map(|account| shares(&env, &rusd_id, DataKey::Shares(account.clone())))
+            .collect();
+        let shares_before = shares(&env, &rusd_id, DataKey::TotalShares);
+        let assets_before = rusd.total_assets();
+        let flows_before: i128 = ledgers.iter().map(|l| l.deposited - l.withdrawn).sum();
+
+        match *op {
+            Op::Mint { to, amount } => {
+                let to = actor(to);
+                let before = usdc.balance(&actors[to]);
+                let _ = rusd.try_mint(&actors[to], &(amount as i128));
+                ledgers[to].deposited += before - usdc.balance(&actors[to]);
+            }
+            Op::Burn { from, amount } => {
+                let from = actor(from);
+                let before = usdc.balance(&actors[from]);
+                let _ = rusd.try_burn(&actors[from], &(amount as i128));
+                ledgers[from].withdrawn += usdc.balance(&actors[from]) - before;
+            }
+            Op::Transfer { from, to, amount } => {
+                let (from, to) = (actor(from), actor(to));
+                if rusd
+                    .try_transfer(&actors[from], &actors[to], &(amount as i128))
+                    .is_ok()
+                {
+                    ledgers[from].sent += amount as i128;
+                    ledgers[to].received += amount as i128;
+                }
+            }
+            Op::Approve {
+                owner,
+                spender,
+                amount,
+            } => {
+                let _ = rusd.try_approve(
+                    &actors[actor(owner)],
+                    &actors[actor(spender)],
+                    &(amount as i128),
+                    &live_until,
+                );
+            }
+            Op::TransferFrom {
+                spender,
+                from,
+                to,
+                amount,
+            } => {
+                let (from, to) = (actor(from), actor(to));
+                if rusd
+                    .try_transfer_from(
+                        &actors[actor(spender)],
+                        &actors[from],
+                        &actors[to],
+                        &(amount as i128),
+                    )
+                    .is_ok()
+                {
+                    ledgers[from].sent += amount as i128;
+                    ledgers[to].received += amount as i128;
+                }
+            }
+            Op::Donate { amount } => {
+                if amount > 0 {
+                    usdc.transfer(&donor, &rusd_id, &(amount as i128));
+                }
+            }
+            Op::Sync => rusd.sync(),
+        }
+
+        let total_shares = shares(&env, &rusd_id, DataKey::TotalShares);
+        let held_shares: i128 = actors
+            .iter()
+            .map(|account| shares(&env, &rusd_id, DataKey::Shares(account.clone())))
+            .sum();
+        assert_eq!(held_shares, total_shares, "shares out of sync");
+
+        let total_supply = rusd.total_supply();
+        assert!(total_supply <= rusd.total_assets(), "supply above assets");
+        assert!(
+            rusd.total_assets() <= usdc.balance(&rusd_id),
+            "assets above USDC held"
+        );
+
+        // Whatever total assets gained beyond the actors' own deposits and
+        // withdrawals is yield, owed to holders by their shares before the step.
+        // Assets that back no shares at all go to whoever holds shares next,
+        // since a mint into an empty supply takes the whole pool.
+        let flows: i128 = ledgers.iter().map(|l| l.deposited - l.withdrawn).sum();
+        let gain = (rusd.total_assets() - assets_before - (flows - flows_before)).max(0);
+        let (pool, weights, weight_total) = if shares_before > 0 {
+            (gain, held_before, shares_before)
+        } else {
+            let held_after = actors
+                .iter()
+                .map(|account| shares(&env, &rusd_id, DataKey::Shares(account.clone())))
+                .collect();
+            (assets_before + gain, held_after, total_shares)
+        };
+        if pool > 0 && weight_total > 0 {
+            for (ledger, held) in ledgers.iter_mut().zip(&weights) {
+                ledger.earned += ceil_div(pool * held, weight_total);
+            }
+        }
+
+        let rounding = step as i128 + 1;
+        for (account, ledger) in actors.iter().zip(ledgers.iter()) {
+            let value = ledger.withdrawn + rusd.balance(account);
+            let entitled = ledger.deposited + ledger.received - ledger.sent + ledger.earned;
+            assert!(
+                value <= entitled + rounding,
+                "account extracted more than deposits plus its share of yield"
+            );
+        }
+    }
+});
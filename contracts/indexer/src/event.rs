@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use stellar_xdr::curr::{Limits, ReadXdr, ScMap, ScSymbol, ScVal};
+
+use crate::Error;
+
+const TRACKED: [&str; 4] = [
+    "shares_minted",
+    "shares_burned",
+    "shares_transferred",
+    "checkpoint_recorded",
+];
+
+// One entry of a `getEvents` result, with topics and value as base64 XDR.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEvent {
+    pub id: String,
+    pub ledger: u32,
+    pub contract_id: String,
+    pub topic: Vec<String>,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ShareEvent {
+    Minted {
+        to: String,
+        shares: i128,
+    },
+    Burned {
+        from: String,
+        shares: i128,
+    },
+    Transferred {
+        from: String,
+        to: String,
+        shares: i128,
+    },
+    Checkpoint {
+        total_assets: i128,
+        total_shares: i128,
+    },
+}
+
+impl ShareEvent {
+    // `Ok(None)` for events the indexer does not track (SEP-41 amounts,
+    // admin events, ...).
+    pub fn decode(event: &RpcEvent) -> Result<Option<Self>, Error> {
+        let malformed = || Error::UnexpectedEvent(event.id.clone());
+        let topics = event
+            .topic
+            .iter()
+            .map(|topic| decode_val(topic))
+            .collect::<Result<Vec<_>, _>>()?;
+        let Some(ScVal::Symbol(name)) = topics.first() else {
+            return Ok(None);
+        };
+        let address = |index: usize| match topics.get(index) {
+            Some(ScVal::Address(address)) => Ok(address.to_string()),
+            _ => Err(malformed()),
+        };
+        let name = name.to_utf8_string_lossy();
+        if !TRACKED.contains(&name.as_str()) {
+            return Ok(None);
+        }
+        let ScVal::Map(Some(fields)) = decode_val(&event.value)? else {
+            return Err(malformed());
+        };
+        let field = |key: &str| i128_field(&fields, key).ok_or_else(malformed);
+        let decoded = match name.as_str() {
+            "shares_minted" => ShareEvent::Minted {
+                to: address(1)?,
+                shares: field("shares")?,
+            },
+            "shares_burned" => ShareEvent::Burned {
+                from: address(1)?,
+                shares: field("shares")?,
+            },
+            "shares_transferred" => ShareEvent::Transferred {
+                from: address(1)?,
+                to: address(2)?,
+                shares: field("shares")?,
+            },
+            _ => ShareEvent::Checkpoint {
+                total_assets: field("total_assets")?,
+                total_shares: field("total_shares")?,
+            },
+        };
+        Ok(Some(decoded))
+    }
+}
+
+fn decode_val(encoded: &str) -> Result<ScVal, Error> {
+    ScVal::from_xdr_base64(encoded, Limits::none()).map_err(|err| Error::Xdr(err.to_string()))
+}
+
+fn i128_field(fields: &ScMap, key: &str) -> Option<i128> {
+    let key = ScVal::Symbol(ScSymbol(key.try_into().ok()?));
+    fields
+        .iter()
+        .find(|entry| entry.key == key)
+        .and_then(|entry| match &entry.val {
+            ScVal::I128(parts) => Some(((parts.hi as i128) << 64) | parts.lo as i128),
+            _ => None,
+        })
+}
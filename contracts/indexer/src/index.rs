@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use rusd_math::rusd_from_shares;
+
+use crate::{Error, RpcEvent, ShareEvent};
+
+// Share balances after every event up to `ledger`. Balances use the backing
+// and share supply of the last checkpoint at or before `ledger`, the same
+// rate `rate_at(ledger)` reports.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    pub ledger: u32,
+    pub shares: BTreeMap<String, i128>,
+    pub total_shares: i128,
+    pub total_assets: i128,
+    pub checkpoint_shares: i128,
+}
+
+impl Snapshot {
+    fn apply(&mut self, ledger: u32, event: &ShareEvent) -> Result<(), Error> {
+        self.ledger = ledger;
+        match event {
+            ShareEvent::Minted { to, shares } => {
+                self.credit(to, *shares)?;
+                self.total_shares = self
+                    .total_shares
+                    .checked_add(*shares)
+                    .ok_or(Error::Overflow)?;
+            }
+            ShareEvent::Burned { from, shares } => {
+                self.credit(from, -shares)?;
+                self.total_shares = self
+                    .total_shares
+                    .checked_sub(*shares)
+                    .ok_or(Error::Overflow)?;
+            }
+            ShareEvent::Transferred { from, to, shares } => {
+                self.credit(from, -shares)?;
+                self.credit(to, *shares)?;
+            }
+            ShareEvent::Checkpoint {
+                total_assets,
+                total_shares,
+            } => {
+                self.total_assets = *total_assets;
+                self.checkpoint_shares = *total_shares;
+            }
+        }
+        Ok(())
+    }
+
+    fn credit(&mut self, holder: &str, shares: i128) -> Result<(), Error> {
+        let current = self.shares_of(holder);
+        let updated = current.checked_add(shares).ok_or(Error::Overflow)?;
+        if updated == 0 {
+            self.shares.remove(holder);
+        } else {
+            self.shares.insert(holder.to_string(), updated);
+        }
+        Ok(())
+    }
+
+    pub fn shares_of(&self, holder: &str) -> i128 {
+        self.shares.get(holder).copied().unwrap_or(0)
+    }
+
+    pub fn balance(&self, holder: &str) -> Result<i128, Error> {
+        rusd_from_shares(
+            self.shares_of(holder),
+            self.checkpoint_shares,
+            self.total_assets,
+        )
+        .map_err(|_| Error::Overflow)
+    }
+
+    pub fn balances(&self) -> Result<BTreeMap<String, i128>, Error> {
+        self.shares
+            .keys()
+            .map(|holder| Ok((holder.clone(), self.balance(holder)?)))
+            .collect()
+    }
+}
+
+pub struct Indexer {
+    contract_id: String,
+    cursor: Option<String>,
+    events: Vec<(u32, ShareEvent)>,
+    state: Snapshot,
+}
+
+impl Indexer {
+    pub fn new(contract_id: impl Into<String>) -> Self {
+        Indexer {
+            contract_id: contract_id.into(),
+            cursor: None,
+            events: Vec::new(),
+            state: Snapshot::default(),
+        }
+    }
+
+    pub fn contract_id(&self) -> &str {
+        &self.contract_id
+    }
+
+    // Id of the last event ingested; resume `getEvents` pagination from it.
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    // Applies events in id order, skipping other contracts and ids at or
+    // before the cursor. Returns how many share events were applied.
+    pub fn ingest(&mut self, events: &[RpcEvent]) -> Result<usize, Error> {
+        let mut applied = 0;
+        for event in events {
+            if event.contract_id != self.contract_id
+                || self
+                    .cursor
+                    .as_deref()
+                    .is_some_and(|cursor| event.id.as_str() <= cursor)
+            {
+                continue;
+            }
+            if let Some(decoded) = ShareEvent::decode(event)? {
+                self.state.apply(event.ledger, &decoded)?;
+                self.events.push((event.ledger, decoded));
+                applied += 1;
+            }
+            self.cursor = Some(event.id.clone());
+        }
+        Ok(applied)
+    }
+
+    pub fn state(&self) -> &Snapshot {
+        &self.state
+    }
+
+    pub fn snapshot(&self, ledger: u32) -> Result<Snapshot, Error> {
+        let mut snapshot = Snapshot::default();
+        for (event_ledger, event) in self.events.iter().take_while(|(at, _)| *at <= ledger) {
+            snapshot.apply(*event_ledger, event)?;
+        }
+        snapshot.ledger = ledger;
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,38 @@
+mod event;
+mod index;
+mod rpc;
+pub mod standin;
+
+pub use event::{RpcEvent, ShareEvent};
+pub use index::{Indexer, Snapshot};
+pub use rpc::{load_fixture, RpcClient};
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    Io(String),
+    Json(String),
+    Rpc(String),
+    Xdr(String),
+    UnexpectedEvent(String),
+    Overflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "io: {err}"),
+            Error::Json(err) => write!(f, "json: {err}"),
+            Error::Rpc(err) => write!(f, "rpc: {err}"),
+            Error::Xdr(err) => write!(f, "xdr: {err}"),
+            Error::UnexpectedEvent(id) => write!(f, "malformed rUSD event {id}"),
+            Error::Overflow => write!(f, "share arithmetic overflowed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod test;
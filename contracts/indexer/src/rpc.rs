@@ -0,0 +1,92 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{Error, Indexer, RpcEvent};
+
+const PAGE_LIMIT: u32 = 100;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventsPage {
+    events: Vec<RpcEvent>,
+    latest_ledger: u32,
+}
+
+#[derive(Deserialize)]
+struct LatestLedger {
+    sequence: u32,
+}
+
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        RpcClient { url: url.into() }
+    }
+
+    fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, Error> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = ureq::post(&self.url)
+            .send_json(request)
+            .map_err(|err| Error::Rpc(err.to_string()))?
+            .into_json()
+            .map_err(|err| Error::Json(err.to_string()))?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::Rpc(error.to_string()));
+        }
+        serde_json::from_value(response["result"].clone())
+            .map_err(|err| Error::Json(err.to_string()))
+    }
+
+    pub fn latest_ledger(&self) -> Result<u32, Error> {
+        self.call::<LatestLedger>("getLatestLedger", json!({}))
+            .map(|ledger| ledger.sequence)
+    }
+
+    // Pages through `getEvents` for the indexer's contract, from its cursor or
+    // from `start_ledger` on the first call. Returns the RPC's latest ledger.
+    pub fn sync(&self, indexer: &mut Indexer, start_ledger: u32) -> Result<u32, Error> {
+        loop {
+            let filters = json!([{ "type": "contract", "contractIds": [indexer.contract_id()] }]);
+            let params = match indexer.cursor() {
+                Some(cursor) => json!({
+                    "filters": filters,
+                    "pagination": { "cursor": cursor, "limit": PAGE_LIMIT },
+                }),
+                None => json!({
+                    "startLedger": start_ledger,
+                    "filters": filters,
+                    "pagination": { "limit": PAGE_LIMIT },
+                }),
+            };
+            let page: EventsPage = self.call("getEvents", params)?;
+            indexer.ingest(&page.events)?;
+            if page.events.len() < PAGE_LIMIT as usize {
+                return Ok(page.latest_ledger);
+            }
+        }
+    }
+}
+
+// Reads events saved from `getEvents`: the full response, its `result`, or a
+// bare array of events.
+pub fn load_fixture(path: &Path) -> Result<Vec<RpcEvent>, Error> {
+    let input = fs::read_to_string(path).map_err(|err| Error::Io(err.to_string()))?;
+    let value: Value = serde_json::from_str(&input).map_err(|err| Error::Json(err.to_string()))?;
+    let events = if value.is_array() {
+        value
+    } else if let Some(result) = value.get("result") {
+        result["events"].clone()
+    } else {
+        value["events"].clone()
+    };
+    serde_json::from_value(events).map_err(|err| Error::Json(err.to_string()))
+}
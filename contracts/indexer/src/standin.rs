@@ -0,0 +1,139 @@
+// Minimal JSON-RPC server answering `getEvents` and `getLatestLedger` from a
+// fixed event list, so `RpcClient` can be exercised without a network.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use serde_json::{json, Value};
+
+use crate::RpcEvent;
+
+pub struct LocalRpc {
+    url: String,
+    address: std::net::SocketAddr,
+    stopped: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LocalRpc {
+    pub fn serve(events: Vec<RpcEvent>, latest_ledger: u32) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stop = stopped.clone();
+        let handle = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let _ = respond(stream, &events, latest_ledger);
+                }
+            }
+        });
+        Ok(LocalRpc {
+            url: format!("http://{address}"),
+            address,
+            stopped,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for LocalRpc {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.address);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn respond(stream: TcpStream, events: &[RpcEvent], latest_ledger: u32) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line == "\r\n" {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    let result = match request["method"].as_str() {
+        Some("getEvents") => get_events(&request["params"], events, latest_ledger),
+        Some("getLatestLedger") => Ok(json!({ "sequence": latest_ledger })),
+        _ => Err("method not found"),
+    };
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+        Err(message) => json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "error": { "code": -32601, "message": message },
+        }),
+    }
+    .to_string();
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+        response.len()
+    )?;
+    stream.flush()
+}
+
+fn get_events(
+    params: &Value,
+    events: &[RpcEvent],
+    latest_ledger: u32,
+) -> Result<Value, &'static str> {
+    let contract_ids: Vec<&str> = params["filters"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|filter| filter["contractIds"].as_array().into_iter().flatten())
+        .filter_map(Value::as_str)
+        .collect();
+    let cursor = params["pagination"]["cursor"].as_str();
+    let start_ledger = params["startLedger"].as_u64();
+    if cursor.is_some() == start_ledger.is_some() {
+        return Err("exactly one of startLedger and pagination.cursor is required");
+    }
+    let limit = params["pagination"]["limit"].as_u64().unwrap_or(100) as usize;
+    let page: Vec<&RpcEvent> = events
+        .iter()
+        .filter(|event| {
+            contract_ids.is_empty() || contract_ids.contains(&event.contract_id.as_str())
+        })
+        .filter(|event| match (cursor, start_ledger) {
+            (Some(cursor), _) => event.id.as_str() > cursor,
+            (None, Some(start)) => u64::from(event.ledger) >= start,
+            (None, None) => true,
+        })
+        .take(limit)
+        .collect();
+    let cursor = page
+        .last()
+        .map(|event| event.id.clone())
+        .unwrap_or_default();
+    Ok(json!({ "events": page, "latestLedger": latest_ledger, "cursor": cursor }))
+}
@@ -0,0 +1,240 @@
+use std::{collections::BTreeMap, path::Path};
+
+use rusd_rebasing_token::testutils::Scenario;
+use soroban_sdk::{
+    testutils::{Address as _, Events as _},
+    xdr::{Limits, ScVal, WriteXdr},
+    Address, TryFromVal, Val,
+};
+
+use crate::{load_fixture, standin::LocalRpc, Error, Indexer, RpcClient, RpcEvent, ShareEvent};
+
+fn encode(scenario: &Scenario, val: &Val) -> String {
+    ScVal::try_from_val(&scenario.env, val)
+        .unwrap()
+        .to_xdr_base64(Limits::none())
+        .unwrap()
+}
+
+// Converts the events of the last invocation into `getEvents` entries, so
+// each step must make a single rUSD call.
+fn capture(scenario: &Scenario, events: &mut Vec<RpcEvent>) {
+    let ledger = scenario.env.ledger().sequence();
+    for (contract, topics, data) in scenario.env.events().all().iter() {
+        events.push(RpcEvent {
+            id: format!("{:019}-{:010}", u64::from(ledger) << 32, events.len()),
+            ledger,
+            contract_id: contract.to_string().to_string(),
+            topic: topics
+                .iter()
+                .map(|topic| encode(scenario, &topic))
+                .collect(),
+            value: encode(scenario, &data),
+        });
+    }
+}
+
+struct Recorded {
+    scenario: Scenario,
+    events: Vec<RpcEvent>,
+    holders: Vec<Address>,
+    balances: Vec<(u32, BTreeMap<String, i128>)>,
+}
+
+impl Recorded {
+    fn contract_id(&self) -> String {
+        self.scenario.rusd.address.to_string().to_string()
+    }
+
+    fn step(&mut self, action: impl FnOnce(&Scenario)) {
+        self.scenario.advance_ledgers(10);
+        action(&self.scenario);
+        capture(&self.scenario, &mut self.events);
+        let balances = self
+            .holders
+            .iter()
+            .map(|holder| {
+                (
+                    holder.to_string().to_string(),
+                    self.scenario.rusd.balance(holder),
+                )
+            })
+            .filter(|(_, balance)| *balance != 0)
+            .collect();
+        self.balances
+            .push((self.scenario.env.ledger().sequence(), balances));
+    }
+}
+
+fn record_history() -> Recorded {
+    let scenario = Scenario::builder().build();
+    let actors = &scenario.actors;
+    let holders = vec![
+        actors.alice.clone(),
+        actors.bob.clone(),
+        actors.carol.clone(),
+    ];
+    let mut recorded = Recorded {
+        scenario,
+        events: Vec::new(),
+        holders,
+        balances: Vec::new(),
+    };
+    recorded.step(|s| s.deposit(&s.actors.alice, 1_000_000));
+    recorded.step(|s| s.deposit(&s.actors.bob, 333_333));
+    recorded.step(|s| {
+        s.rusd.transfer(&s.actors.alice, &s.actors.carol, &250_001);
+    });
+    recorded.step(|s| s.simulate_yield(77_777));
+    recorded.step(|s| s.rusd.burn(&s.actors.bob, &100_003));
+    recorded.step(|s| {
+        let payouts = soroban_sdk::vec![
+            &s.env,
+            (s.actors.bob.clone(), 12_345),
+            (s.actors.carol.clone(), 1),
+        ];
+        s.rusd.batch_transfer(&s.actors.alice, &payouts);
+    });
+    recorded.step(|s| {
+        s.rusd.request_redeem(&s.actors.carol, &50_000);
+    });
+    recorded.step(|s| s.rusd.cancel_redeem(&0));
+    recorded.step(|s| {
+        s.rusd.request_redeem(&s.actors.alice, &300_000);
+    });
+    recorded.step(|s| s.simulate_yield(5_000));
+    recorded.step(|s| {
+        s.rusd.process_queue(&10);
+    });
+    recorded
+}
+
+#[test]
+fn snapshots_match_contract_balances_at_every_ledger() {
+    let recorded = record_history();
+    let mut indexer = Indexer::new(recorded.contract_id());
+    let applied = indexer.ingest(&recorded.events).unwrap();
+    assert!(applied > 0 && applied < recorded.events.len());
+
+    for (ledger, balances) in &recorded.balances {
+        let snapshot = indexer.snapshot(*ledger).unwrap();
+        let mut holders = snapshot.balances().unwrap();
+        holders.remove(&recorded.contract_id());
+        assert_eq!(&holders, balances, "ledger {ledger}");
+        assert_eq!(
+            snapshot.shares.values().sum::<i128>(),
+            snapshot.total_shares
+        );
+        assert_eq!(snapshot.total_shares, snapshot.checkpoint_shares);
+    }
+
+    let rusd = &recorded.scenario.rusd;
+    let state = indexer.state();
+    assert_eq!(state.total_assets, rusd.total_assets());
+    assert_eq!(
+        state.shares_of(&recorded.contract_id()),
+        rusd.queued_shares()
+    );
+    assert_eq!(indexer.snapshot(0).unwrap().shares, BTreeMap::new());
+
+    // Re-ingesting the same page is a no-op.
+    assert_eq!(indexer.ingest(&recorded.events), Ok(0));
+}
+
+#[test]
+fn ingest_skips_other_contracts_and_untracked_events() {
+    let recorded = record_history();
+    let mut indexer = Indexer::new(
+        Address::generate(&recorded.scenario.env)
+            .to_string()
+            .to_string(),
+    );
+    assert_eq!(indexer.ingest(&recorded.events), Ok(0));
+    assert_eq!(indexer.state().total_shares, 0);
+
+    let usdc = recorded.scenario.usdc.address.to_string().to_string();
+    let untracked = recorded
+        .events
+        .iter()
+        .filter(|event| event.contract_id == usdc)
+        .chain(
+            recorded
+                .events
+                .iter()
+                .filter(|event| event.topic.len() == 3),
+        )
+        .filter(|event| ShareEvent::decode(event).unwrap().is_none())
+        .count();
+    assert!(untracked > 0);
+
+    let mut malformed = recorded
+        .events
+        .iter()
+        .find(|event| {
+            matches!(
+                ShareEvent::decode(event),
+                Ok(Some(ShareEvent::Minted { .. }))
+            )
+        })
+        .unwrap()
+        .clone();
+    malformed.topic.truncate(1);
+    assert_eq!(
+        ShareEvent::decode(&malformed),
+        Err(Error::UnexpectedEvent(malformed.id.clone()))
+    );
+    malformed.value = "not xdr".into();
+    assert!(matches!(ShareEvent::decode(&malformed), Err(Error::Xdr(_))));
+}
+
+#[test]
+fn fixture_replays_to_point_in_time_balances() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/get_events.json");
+    let events = load_fixture(&path).unwrap();
+    let contract_id = events[0].contract_id.clone();
+    let mut indexer = Indexer::new(contract_id);
+    indexer.ingest(&events).unwrap();
+
+    let before_yield = indexer.snapshot(31).unwrap();
+    let after_yield = indexer.snapshot(41).unwrap();
+    for (holder, balance) in before_yield.balances().unwrap() {
+        assert!(after_yield.balance(&holder).unwrap() >= balance);
+    }
+    assert!(after_yield.total_assets > before_yield.total_assets);
+    assert_eq!(after_yield.total_shares, before_yield.total_shares);
+}
+
+#[test]
+fn local_rpc_pages_events_into_the_indexer() {
+    let mut recorded = record_history();
+    for _ in 0..40 {
+        recorded.step(|s| s.rusd.transfer(&s.actors.alice, &s.actors.bob, &3));
+        recorded.step(|s| s.rusd.transfer(&s.actors.bob, &s.actors.carol, &2));
+    }
+    let (first, rest) = recorded.events.split_at(recorded.events.len() / 2);
+    let latest = recorded.scenario.env.ledger().sequence();
+
+    let mut indexer = Indexer::new(recorded.contract_id());
+    let rpc = LocalRpc::serve(first.to_vec(), latest).unwrap();
+    let client = RpcClient::new(rpc.url());
+    assert_eq!(client.latest_ledger(), Ok(latest));
+    assert_eq!(client.sync(&mut indexer, 0), Ok(latest));
+    drop(rpc);
+
+    let rpc = LocalRpc::serve(recorded.events.clone(), latest).unwrap();
+    let client = RpcClient::new(rpc.url());
+    client.sync(&mut indexer, 0).unwrap();
+    assert_eq!(indexer.cursor(), rest.last().map(|event| event.id.as_str()));
+
+    let mut direct = Indexer::new(recorded.contract_id());
+    direct.ingest(&recorded.events).unwrap();
+    assert_eq!(indexer.state(), direct.state());
+    let holder = recorded.scenario.actors.carol.to_string().to_string();
+    assert_eq!(
+        indexer.state().balance(&holder),
+        Ok(recorded
+            .scenario
+            .rusd
+            .balance(&recorded.scenario.actors.carol))
+    );
+}
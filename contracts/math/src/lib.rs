@@ -0,0 +1,62 @@
+#![no_std]
+
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MathError {
+    DivisionByZero,
+    Overflow,
+}
+
+pub fn mul_div_floor(a: i128, b: i128, denom: i128) -> Result<i128, MathError> {
+    if denom == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    let prod = a.checked_mul(b).ok_or(MathError::Overflow)?;
+    Ok(prod / denom)
+}
+
+pub fn mul_div_ceil(a: i128, b: i128, denom: i128) -> Result<i128, MathError> {
+    if denom == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    let prod = a.checked_mul(b).ok_or(MathError::Overflow)?;
+    let div = prod / denom;
+    if prod % denom == 0 {
+        Ok(div)
+    } else {
+        Ok(div + 1)
+    }
+}
+
+pub fn shares_from_rusd(
+    amount: i128,
+    total_shares: i128,
+    underlying: i128,
+) -> Result<i128, MathError> {
+    if total_shares == 0 {
+        return Ok(amount);
+    }
+    if underlying == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    mul_div_ceil(amount, total_shares, underlying)
+}
+
+pub fn rusd_from_shares(
+    shares: i128,
+    total_shares: i128,
+    underlying: i128,
+) -> Result<i128, MathError> {
+    if total_shares == 0 || shares == 0 {
+        return Ok(0);
+    }
+    mul_div_floor(shares, underlying, total_shares)
+}
+
+pub fn bps_of(amount: i128, bps: u32) -> Result<i128, MathError> {
+    mul_div_floor(amount, bps as i128, BPS_DENOMINATOR)
+}
+
+#[cfg(test)]
+mod test;
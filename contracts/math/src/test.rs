@@ -0,0 +1,22 @@
+#![cfg(test)]
+
+use crate::{bps_of, mul_div_ceil, mul_div_floor, rusd_from_shares, shares_from_rusd, MathError};
+
+#[test]
+fn mul_div_rounds_in_opposite_directions() {
+    assert_eq!(mul_div_floor(10, 3, 4), Ok(7));
+    assert_eq!(mul_div_ceil(10, 3, 4), Ok(8));
+    assert_eq!(mul_div_ceil(12, 3, 4), Ok(9));
+    assert_eq!(mul_div_floor(1, 1, 0), Err(MathError::DivisionByZero));
+    assert_eq!(mul_div_ceil(i128::MAX, 2, 1), Err(MathError::Overflow));
+}
+
+#[test]
+fn conversions_bootstrap_and_round_against_the_caller() {
+    assert_eq!(shares_from_rusd(100, 0, 0), Ok(100));
+    assert_eq!(rusd_from_shares(100, 0, 0), Ok(0));
+    assert_eq!(shares_from_rusd(1, 3, 10), Ok(1));
+    assert_eq!(rusd_from_shares(1, 3, 10), Ok(3));
+    assert_eq!(shares_from_rusd(1, 3, 0), Err(MathError::DivisionByZero));
+    assert_eq!(bps_of(1_999, 5_000), Ok(999));
+}
@@ -0,0 +1,326 @@
+use std::{collections::BTreeMap, fmt};
+
+use rusd_math::{
+    bps_of, mul_div_floor, rusd_from_shares, shares_from_rusd, MathError, BPS_DENOMINATOR,
+};
+use serde::Deserialize;
+
+pub const RATE_SCALE: i128 = 1_000_000_000_000;
+const SECONDS_PER_YEAR: i128 = 31_536_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    Mint {
+        account: String,
+        amount: i128,
+    },
+    Burn {
+        account: String,
+        amount: i128,
+    },
+    Transfer {
+        from: String,
+        to: String,
+        amount: i128,
+    },
+    Yield {
+        amount: i128,
+    },
+    Loss {
+        amount: i128,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(try_from = "Row")]
+pub struct Step {
+    pub time: u64,
+    pub repeat: u64,
+    pub op: Op,
+}
+
+// One scenario line, shared by the JSON and CSV formats. `account` (or `from`)
+// is the sender for transfers; `yield` and `loss` take no account.
+#[derive(Deserialize)]
+struct Row {
+    #[serde(default)]
+    time: u64,
+    op: String,
+    #[serde(default, alias = "from")]
+    account: String,
+    #[serde(default)]
+    to: String,
+    amount: i64,
+    #[serde(default)]
+    repeat: Option<u64>,
+}
+
+impl TryFrom<Row> for Step {
+    type Error = String;
+
+    fn try_from(row: Row) -> Result<Self, String> {
+        let amount = row.amount as i128;
+        let op = match row.op.as_str() {
+            "mint" => Op::Mint {
+                account: row.account,
+                amount,
+            },
+            "burn" => Op::Burn {
+                account: row.account,
+                amount,
+            },
+            "transfer" => Op::Transfer {
+                from: row.account,
+                to: row.to,
+                amount,
+            },
+            "yield" => Op::Yield { amount },
+            "loss" => Op::Loss { amount },
+            other => return Err(format!("unknown op `{other}`")),
+        };
+        Ok(Step {
+            time: row.time,
+            repeat: row.repeat.unwrap_or(1),
+            op,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Scenario {
+    #[serde(default)]
+    pub fee_bps: u32,
+    pub steps: Vec<Step>,
+}
+
+impl Scenario {
+    pub fn from_json(input: &str) -> Result<Self, String> {
+        serde_json::from_str(input).map_err(|err| err.to_string())
+    }
+
+    // Columns: time,op,account,to,amount,repeat (header line required).
+    pub fn from_csv(input: &str) -> Result<Self, String> {
+        let mut steps = Vec::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if index == 0 || line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {message}", index + 1);
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() < 5 {
+                return Err(error("expected at least 5 columns".into()));
+            }
+            let number = |value: &str| -> Result<i64, String> {
+                value
+                    .parse()
+                    .map_err(|_| error(format!("invalid number `{value}`")))
+            };
+            let repeat = match fields.get(5) {
+                Some(value) if !value.is_empty() => Some(number(value)? as u64),
+                _ => None,
+            };
+            let row = Row {
+                time: number(fields[0])? as u64,
+                op: fields[1].to_string(),
+                account: fields[2].to_string(),
+                to: fields[3].to_string(),
+                amount: number(fields[4])?,
+                repeat,
+            };
+            steps.push(Step::try_from(row).map_err(error)?);
+        }
+        Ok(Scenario { fee_bps: 0, steps })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SimError {
+    ZeroAmount,
+    InsufficientShares,
+    Math(MathError),
+}
+
+impl From<MathError> for SimError {
+    fn from(err: MathError) -> Self {
+        SimError::Math(err)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Account {
+    pub shares: i128,
+    pub deposited: i128,
+    pub withdrawn: i128,
+}
+
+#[derive(Debug, Default)]
+pub struct Simulator {
+    pub fee_bps: u32,
+    pub total_shares: i128,
+    pub underlying: i128,
+    pub fees_paid: i128,
+    pub applied: u64,
+    pub rejected: u64,
+    pub accounts: BTreeMap<String, Account>,
+    now: u64,
+    start: Option<(u64, i128)>,
+}
+
+impl Simulator {
+    pub fn new(fee_bps: u32) -> Self {
+        Simulator {
+            fee_bps,
+            ..Default::default()
+        }
+    }
+
+    pub fn run(&mut self, scenario: &Scenario) {
+        for step in &scenario.steps {
+            self.now = self.now.max(step.time);
+            for _ in 0..step.repeat {
+                match self.apply(&step.op) {
+                    Ok(()) => self.applied += 1,
+                    Err(_) => self.rejected += 1,
+                }
+            }
+        }
+    }
+
+    pub fn apply(&mut self, op: &Op) -> Result<(), SimError> {
+        match op {
+            Op::Mint { account, amount } => {
+                require_positive(*amount)?;
+                let received = amount - bps_of(*amount, self.fee_bps)?;
+                require_positive(received)?;
+                let shares = shares_from_rusd(received, self.total_shares, self.underlying)?;
+                self.fees_paid += amount - received;
+                self.underlying += received;
+                self.total_shares += shares;
+                let entry = self.accounts.entry(account.clone()).or_default();
+                entry.shares += shares;
+                entry.deposited += amount;
+            }
+            Op::Burn { account, amount } => {
+                require_positive(*amount)?;
+                let shares = shares_from_rusd(*amount, self.total_shares, self.underlying)?;
+                if self.shares_of(account) < shares {
+                    return Err(SimError::InsufficientShares);
+                }
+                let out = rusd_from_shares(shares, self.total_shares, self.underlying)?;
+                let fee = bps_of(out, self.fee_bps)?;
+                self.fees_paid += fee;
+                self.underlying -= out;
+                self.total_shares -= shares;
+                let entry = self.accounts.entry(account.clone()).or_default();
+                entry.shares -= shares;
+                entry.withdrawn += out - fee;
+            }
+            Op::Transfer { from, to, amount } => {
+                require_positive(*amount)?;
+                let shares = shares_from_rusd(*amount, self.total_shares, self.underlying)?;
+                if self.shares_of(from) < shares {
+                    return Err(SimError::InsufficientShares);
+                }
+                self.accounts.entry(from.clone()).or_default().shares -= shares;
+                self.accounts.entry(to.clone()).or_default().shares += shares;
+            }
+            Op::Yield { amount } => {
+                require_positive(*amount)?;
+                self.underlying += amount;
+            }
+            Op::Loss { amount } => {
+                require_positive(*amount)?;
+                self.underlying -= (*amount).min(self.underlying);
+            }
+        }
+        if self.start.is_none() {
+            self.start = self.rate().map(|rate| (self.now, rate));
+        }
+        Ok(())
+    }
+
+    pub fn shares_of(&self, account: &str) -> i128 {
+        self.accounts.get(account).map_or(0, |entry| entry.shares)
+    }
+
+    pub fn balance(&self, account: &str) -> i128 {
+        rusd_from_shares(self.shares_of(account), self.total_shares, self.underlying).unwrap_or(0)
+    }
+
+    pub fn rate(&self) -> Option<i128> {
+        if self.total_shares == 0 {
+            return None;
+        }
+        mul_div_floor(self.underlying, RATE_SCALE, self.total_shares).ok()
+    }
+
+    // rUSD backing no displayed balance can claim, left behind by floor rounding.
+    pub fn dust(&self) -> i128 {
+        let claimed: i128 = self.accounts.keys().map(|name| self.balance(name)).sum();
+        self.underlying - claimed
+    }
+
+    pub fn apy_bps(&self) -> Option<i128> {
+        let (since, rate_then) = self.start?;
+        let elapsed = self.now.checked_sub(since).filter(|elapsed| *elapsed > 0)? as i128;
+        let growth = mul_div_floor(
+            self.rate()? - rate_then,
+            BPS_DENOMINATOR * SECONDS_PER_YEAR,
+            rate_then,
+        )
+        .ok()?;
+        Some(growth / elapsed)
+    }
+}
+
+fn require_positive(amount: i128) -> Result<(), SimError> {
+    if amount <= 0 {
+        return Err(SimError::ZeroAmount);
+    }
+    Ok(())
+}
+
+impl fmt::Display for Simulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "operations: {} applied, {} rejected",
+            self.applied, self.rejected
+        )?;
+        writeln!(f, "total assets: {}", self.underlying)?;
+        writeln!(f, "total shares: {}", self.total_shares)?;
+        match self.rate() {
+            Some(rate) => writeln!(f, "rate: {rate} (x1e12)")?,
+            None => writeln!(f, "rate: -")?,
+        }
+        match self.apy_bps() {
+            Some(apy) => writeln!(f, "apy: {apy} bps")?,
+            None => writeln!(f, "apy: -")?,
+        }
+        writeln!(f, "dust: {}", self.dust())?;
+        writeln!(f, "fees paid: {}", self.fees_paid)?;
+        writeln!(
+            f,
+            "\n{:<16} {:>20} {:>20} {:>20} {:>20} {:>20}",
+            "account", "shares", "balance", "deposited", "withdrawn", "pnl"
+        )?;
+        for (name, account) in &self.accounts {
+            let balance = self.balance(name);
+            writeln!(
+                f,
+                "{:<16} {:>20} {:>20} {:>20} {:>20} {:>20}",
+                name,
+                account.shares,
+                balance,
+                account.deposited,
+                account.withdrawn,
+                account.withdrawn + balance - account.deposited
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,39 @@
+use std::{env, fs, process};
+
+use rusd_sim::{Scenario, Simulator};
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let Some(path) = args.next() else {
+        eprintln!("usage: rusd-sim <scenario.json|scenario.csv> [--fee-bps N]");
+        process::exit(2);
+    };
+    let mut fee_override = None;
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next().map(|value| value.parse::<u32>())) {
+            ("--fee-bps", Some(Ok(bps))) => fee_override = Some(bps),
+            _ => {
+                eprintln!("unexpected argument `{arg}`");
+                process::exit(2);
+            }
+        }
+    }
+
+    let input = fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("cannot read {path}: {err}");
+        process::exit(1);
+    });
+    let parsed = if path.ends_with(".csv") {
+        Scenario::from_csv(&input)
+    } else {
+        Scenario::from_json(&input)
+    };
+    let scenario = parsed.unwrap_or_else(|err| {
+        eprintln!("invalid scenario {path}: {err}");
+        process::exit(1);
+    });
+
+    let mut simulator = Simulator::new(fee_override.unwrap_or(scenario.fee_bps));
+    simulator.run(&scenario);
+    print!("{simulator}");
+}
@@ -0,0 +1,80 @@
+use crate::{Op, Scenario, SimError, Simulator};
+
+fn mint(account: &str, amount: i128) -> Op {
+    Op::Mint {
+        account: account.into(),
+        amount,
+    }
+}
+
+#[test]
+fn rebases_like_the_contract() {
+    let mut sim = Simulator::new(0);
+    sim.apply(&mint("alice", 300)).unwrap();
+    sim.apply(&mint("bob", 100)).unwrap();
+    sim.apply(&Op::Yield { amount: 40 }).unwrap();
+    assert_eq!(sim.balance("alice"), 330);
+    assert_eq!(sim.balance("bob"), 110);
+    assert_eq!(
+        sim.apply(&Op::Burn {
+            account: "bob".into(),
+            amount: 111
+        }),
+        Err(SimError::InsufficientShares)
+    );
+    assert_eq!(sim.apply(&mint("bob", 0)), Err(SimError::ZeroAmount));
+}
+
+#[test]
+fn transfer_fees_reduce_minted_shares_and_payouts() {
+    let mut sim = Simulator::new(100);
+    sim.apply(&mint("alice", 1_000)).unwrap();
+    sim.apply(&mint("bob", 500)).unwrap();
+    sim.apply(&Op::Yield { amount: 1_000 }).unwrap();
+    assert_eq!(sim.balance("alice"), 990 + 666);
+    assert_eq!(sim.balance("bob"), 495 + 333);
+    sim.apply(&Op::Burn {
+        account: "bob".into(),
+        amount: 825,
+    })
+    .unwrap();
+    assert_eq!(sim.accounts["bob"].withdrawn, 818);
+    assert_eq!(sim.fees_paid, 10 + 5 + 8);
+}
+
+#[test]
+fn replays_scenario_files() {
+    let scenario = Scenario::from_json(include_str!("../scenarios/yield_year.json")).unwrap();
+    let mut sim = Simulator::new(scenario.fee_bps);
+    sim.run(&scenario);
+    assert_eq!(sim.rejected, 0);
+    assert_eq!(sim.apy_bps(), Some(400));
+    assert_eq!(sim.balance("carol"), 1_019_607_843);
+
+    let scenario = Scenario::from_csv(include_str!("../scenarios/dust.csv")).unwrap();
+    assert_eq!(scenario.steps.len(), 6);
+    assert_eq!(scenario.steps[3].repeat, 1_000_000);
+    let mut sim = Simulator::new(0);
+    sim.run(&scenario);
+    assert_eq!(sim.applied + sim.rejected, 2_100_003);
+    assert!(sim.dust() >= 0);
+    let paid_out: i128 = sim.accounts.values().map(|account| account.withdrawn).sum();
+    assert_eq!(sim.rejected, 0);
+    assert_eq!(sim.underlying + paid_out, 20_000_000 + 777_777);
+}
+
+#[test]
+fn csv_and_json_describe_the_same_steps() {
+    let json = Scenario::from_json(
+        r#"{ "steps": [
+            { "time": 5, "op": "transfer", "from": "a", "to": "b", "amount": 3, "repeat": 2 },
+            { "op": "loss", "amount": 1 }
+        ] }"#,
+    )
+    .unwrap();
+    let csv =
+        Scenario::from_csv("time,op,account,to,amount,repeat\n5,transfer,a,b,3,2\n0,loss,,,1,\n")
+            .unwrap();
+    assert_eq!(json, csv);
+    assert!(Scenario::from_csv("header\n0,swap,a,,1\n").is_err());
+}
@@ -0,0 +1,183 @@
+use soroban_sdk::{contractevent, contractimpl, contracttype, panic_with_error, Env};
+use stellar_macros::only_admin;
+
+use crate::{
+    checkpoints, collateral, lock::ReentrancyLock, mul_div_ceil, mul_div_floor, oracle::Pricing,
+    timelock, Context, DataKey, Error, RUsdToken, RUsdTokenArgs, RUsdTokenClient,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct YieldDrip {
+    pub amount: i128,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sync {
+    pub gain: i128,
+    pub loss: i128,
+    pub total_assets: i128,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    pub fn sync(env: Env) {
+        let _lock = ReentrancyLock::acquire(&env);
+        let mut ctx = Context::load(&env);
+        let event = settle(&env, &mut ctx);
+        checkpoints::record(&env, &ctx);
+        event.publish(&env);
+    }
+
+    pub fn total_assets(env: Env) -> i128 {
+        read_total_assets(&env)
+    }
+
+    pub fn yield_drip(env: Env) -> Option<YieldDrip> {
+        read_drip(&env)
+    }
+
+    #[only_admin]
+    pub fn set_drip_period(env: Env, seconds: u64) {
+        let _lock = ReentrancyLock::acquire(&env);
+        timelock::require_unlocked(&env);
+        apply_drip_period(&env, seconds);
+    }
+
+    pub fn drip_period(env: Env) -> u64 {
+        read_drip_period(&env)
+    }
+}
+
+// Redemptions convert value into units at the live oracle price, so the
+// accounted assets are marked to that price first. Otherwise a depeg that has
+// not been synced yet pays the first redeemers out of the others' backing.
+pub(crate) fn mark_to_market(env: &Env, ctx: &mut Context, pricing: &Pricing) {
+    if !pricing.is_live() {
+        return;
+    }
+    let event = settle(env, ctx);
+    if event.gain != 0 || event.loss != 0 {
+        event.publish(env);
+    }
+}
+
+fn settle(env: &Env, ctx: &mut Context) -> Sync {
+    let measured = collateral::read_backing(env, &ctx.config);
+    let now = env.ledger().timestamp();
+    let drip = read_drip(env);
+    let mut unvested = drip
+        .as_ref()
+        .map(|drip| drip.amount - vested(env, drip))
+        .unwrap_or(0);
+    let mut end = drip.map(|drip| drip.end).unwrap_or(now);
+    let unaccounted = measured - ctx.underlying - unvested;
+    let (gain, loss) = if unaccounted >= 0 {
+        (unaccounted, 0)
+    } else {
+        (0, -unaccounted)
+    };
+    let absorbed = loss.min(unvested);
+    unvested -= absorbed;
+    ctx.underlying -= loss - absorbed;
+
+    let period = read_drip_period(env);
+    if period == 0 {
+        ctx.underlying += unvested + gain;
+        unvested = 0;
+    } else if gain > 0 {
+        end = drip_end(env, now, unvested, end, gain, period);
+        unvested += gain;
+    }
+    write_total_assets(env, ctx.underlying);
+    if unvested > 0 {
+        env.storage().instance().set(
+            &DataKey::YieldDrip,
+            &YieldDrip {
+                amount: unvested,
+                start: now,
+                end: end.max(now + 1),
+            },
+        );
+    } else {
+        env.storage().instance().remove(&DataKey::YieldDrip);
+    }
+    Sync {
+        gain,
+        loss,
+        total_assets: ctx.underlying,
+    }
+}
+
+pub(crate) fn apply_drip_period(env: &Env, seconds: u64) {
+    env.storage().instance().set(&DataKey::DripPeriod, &seconds);
+}
+
+fn read_drip_period(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::DripPeriod)
+        .unwrap_or(0)
+}
+
+// A new gain vests over a full period while the remainder keeps its own end;
+// the combined drip ends at the amount-weighted average of the two, so calls
+// without a gain never move the end and dust gains barely do.
+fn drip_end(env: &Env, now: u64, unvested: i128, end: u64, gain: i128, period: u64) -> u64 {
+    let remaining = end.saturating_sub(now) as i128;
+    let weighted = unvested
+        .checked_mul(remaining)
+        .and_then(|old| gain.checked_mul(period as i128)?.checked_add(old))
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    now + mul_div_ceil(env, weighted, 1, unvested + gain) as u64
+}
+
+fn read_drip(env: &Env) -> Option<YieldDrip> {
+    env.storage().instance().get(&DataKey::YieldDrip)
+}
+
+fn vested(env: &Env, drip: &YieldDrip) -> i128 {
+    let now = env.ledger().timestamp();
+    if now >= drip.end {
+        return drip.amount;
+    }
+    let elapsed = now.saturating_sub(drip.start) as i128;
+    mul_div_floor(env, drip.amount, elapsed, (drip.end - drip.start) as i128)
+}
+
+pub(crate) fn read_total_assets(env: &Env) -> i128 {
+    let accounted: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKey::TotalAssets)
+        .unwrap_or(0);
+    match read_drip(env) {
+        Some(drip) => accounted + vested(env, &drip),
+        None => accounted,
+    }
+}
+
+pub(crate) fn write_total_assets(env: &Env, total_assets: i128) {
+    if let Some(drip) = read_drip(env) {
+        let remaining = drip.amount - vested(env, &drip);
+        if remaining == 0 {
+            env.storage().instance().remove(&DataKey::YieldDrip);
+        } else {
+            let now = env.ledger().timestamp();
+            env.storage().instance().set(
+                &DataKey::YieldDrip,
+                &YieldDrip {
+                    amount: remaining,
+                    start: now.max(drip.start),
+                    end: drip.end,
+                },
+            );
+        }
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::TotalAssets, &total_assets);
+}
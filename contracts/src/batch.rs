@@ -0,0 +1,68 @@
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env, Vec};
+use stellar_tokens::fungible::emit_transfer;
+
+use crate::{
+    compliance, cost_basis, lock::ReentrancyLock, read_shares, require_positive_amount,
+    write_shares, Context, Error, RUsdToken, RUsdTokenArgs, RUsdTokenClient, SharesTransferred,
+};
+
+const MAX_BATCH_SIZE: u32 = 50;
+
+#[contractimpl]
+impl RUsdToken {
+    pub fn batch_transfer(env: Env, from: Address, payouts: Vec<(Address, i128)>) {
+        let _lock = ReentrancyLock::acquire(&env);
+        let ctx = Context::load(&env);
+        batch_move(&env, &from, &payouts, |amount| {
+            let shares = ctx.shares_from_rusd(&env, amount);
+            (shares, amount)
+        });
+    }
+
+    pub fn batch_transfer_shares(env: Env, from: Address, payouts: Vec<(Address, i128)>) {
+        let _lock = ReentrancyLock::acquire(&env);
+        let ctx = Context::load(&env);
+        batch_move(&env, &from, &payouts, |shares| {
+            let amount = ctx.rusd_from_shares(&env, shares);
+            (shares, amount)
+        });
+    }
+}
+
+fn batch_move(
+    env: &Env,
+    from: &Address,
+    payouts: &Vec<(Address, i128)>,
+    convert: impl Fn(i128) -> (i128, i128),
+) {
+    if payouts.len() > MAX_BATCH_SIZE {
+        panic_with_error!(env, Error::BatchTooLarge);
+    }
+    from.require_auth();
+    compliance::require_can_hold(env, from);
+    let mut from_shares = read_shares(env, from);
+    for (to, value) in payouts.iter() {
+        require_positive_amount(env, value);
+        compliance::require_can_hold(env, &to);
+        let (shares, amount) = convert(value);
+        if from_shares < shares {
+            panic_with_error!(env, Error::InsufficientShares);
+        }
+        if to != *from {
+            cost_basis::move_principal(env, from, &to, amount, shares, from_shares);
+            from_shares -= shares;
+            let to_shares = read_shares(env, &to)
+                .checked_add(shares)
+                .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+            write_shares(env, &to, to_shares);
+        }
+        emit_transfer(env, from, &to, amount);
+        SharesTransferred {
+            from: from.clone(),
+            to,
+            shares,
+        }
+        .publish(env);
+    }
+    write_shares(env, from, from_shares);
+}
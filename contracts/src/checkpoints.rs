@@ -0,0 +1,169 @@
+use rusd_math::BPS_DENOMINATOR;
+use soroban_sdk::{contractevent, contractimpl, contracttype, Env, Vec};
+
+use crate::{
+    lock::ReentrancyLock, mul_div_floor, Context, DataKey, RUsdToken, RUsdTokenArgs,
+    RUsdTokenClient,
+};
+
+// One checkpoint per hour for 90 days. Later changes within an hour overwrite
+// that hour's checkpoint, so calling `checkpoint()` repeatedly cannot push
+// older history out of the ring.
+const BUCKET_SECONDS: u64 = 3_600;
+const MAX_CHECKPOINTS: u32 = 24 * 90;
+const DAY_IN_LEDGERS: u32 = 17_280;
+const CHECKPOINT_EXTEND_AMOUNT: u32 = 120 * DAY_IN_LEDGERS;
+const CHECKPOINT_TTL_THRESHOLD: u32 = CHECKPOINT_EXTEND_AMOUNT - DAY_IN_LEDGERS;
+const RATE_SCALE: i128 = 1_000_000_000_000;
+const SECONDS_PER_YEAR: i128 = 31_536_000;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub timestamp: u64,
+    pub total_assets: i128,
+    pub total_shares: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CheckpointRecorded {
+    pub total_assets: i128,
+    pub total_shares: i128,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    pub fn checkpoint(env: Env) {
+        let _lock = ReentrancyLock::acquire(&env);
+        record(&env, &Context::load(&env));
+    }
+
+    pub fn checkpoint_count(env: Env) -> u32 {
+        read_count(&env)
+    }
+
+    pub fn checkpoints(env: Env, from: u32, limit: u32) -> Vec<Checkpoint> {
+        let count = read_count(&env);
+        let mut index = from.max(oldest_index(count));
+        let mut result = Vec::new(&env);
+        while index < count && result.len() < limit {
+            result.push_back(read_checkpoint(&env, index));
+            index += 1;
+        }
+        result
+    }
+
+    pub fn rate_at(env: Env, ledger: u32) -> Option<i128> {
+        find_at(&env, ledger).map(|checkpoint| rate(&env, &checkpoint))
+    }
+
+    pub fn apy(env: Env, window_ledgers: u32) -> i128 {
+        let now = current(&env, &Context::load(&env));
+        let Some(then) = find_at(&env, now.ledger.saturating_sub(window_ledgers)) else {
+            return 0;
+        };
+        let elapsed = now.timestamp.saturating_sub(then.timestamp) as i128;
+        if elapsed == 0 {
+            return 0;
+        }
+        let rate_then = rate(&env, &then);
+        let annualized = mul_div_floor(
+            &env,
+            rate(&env, &now) - rate_then,
+            BPS_DENOMINATOR * SECONDS_PER_YEAR,
+            rate_then,
+        );
+        annualized / elapsed
+    }
+}
+
+fn current(env: &Env, ctx: &Context) -> Checkpoint {
+    Checkpoint {
+        ledger: env.ledger().sequence(),
+        timestamp: env.ledger().timestamp(),
+        total_assets: ctx.underlying,
+        total_shares: ctx.total_shares,
+    }
+}
+
+fn rate(env: &Env, checkpoint: &Checkpoint) -> i128 {
+    if checkpoint.total_shares == 0 {
+        return RATE_SCALE;
+    }
+    mul_div_floor(
+        env,
+        checkpoint.total_assets,
+        RATE_SCALE,
+        checkpoint.total_shares,
+    )
+}
+
+fn read_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::CheckpointCount)
+        .unwrap_or(0)
+}
+
+fn oldest_index(count: u32) -> u32 {
+    count.saturating_sub(MAX_CHECKPOINTS)
+}
+
+fn read_checkpoint(env: &Env, index: u32) -> Checkpoint {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Checkpoint(index % MAX_CHECKPOINTS))
+        .unwrap()
+}
+
+fn write_checkpoint(env: &Env, index: u32, checkpoint: &Checkpoint) {
+    let key = DataKey::Checkpoint(index % MAX_CHECKPOINTS);
+    env.storage().persistent().set(&key, checkpoint);
+    env.storage().persistent().extend_ttl(
+        &key,
+        CHECKPOINT_TTL_THRESHOLD,
+        CHECKPOINT_EXTEND_AMOUNT,
+    );
+}
+
+// Checkpoints are ordered by ledger, so the latest one at or before `ledger`
+// is found by bisection over the retained range.
+fn find_at(env: &Env, ledger: u32) -> Option<Checkpoint> {
+    let count = read_count(env);
+    let mut low = oldest_index(count);
+    let mut high = count;
+    let mut found = None;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let checkpoint = read_checkpoint(env, mid);
+        if checkpoint.ledger <= ledger {
+            found = Some(checkpoint);
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    found
+}
+
+pub(crate) fn record(env: &Env, ctx: &Context) {
+    let checkpoint = current(env, ctx);
+    let mut count = read_count(env);
+    if count > 0 {
+        let latest = read_checkpoint(env, count - 1);
+        if latest.timestamp / BUCKET_SECONDS == checkpoint.timestamp / BUCKET_SECONDS {
+            count -= 1;
+        }
+    }
+    write_checkpoint(env, count, &checkpoint);
+    env.storage()
+        .instance()
+        .set(&DataKey::CheckpointCount, &(count + 1));
+    CheckpointRecorded {
+        total_assets: checkpoint.total_assets,
+        total_shares: checkpoint.total_shares,
+    }
+    .publish(env);
+}
@@ -0,0 +1,215 @@
+use rusd_math::BPS_DENOMINATOR;
+use soroban_sdk::{
+    contractevent, contractimpl, contracttype, panic_with_error, token::Client as TokenClient,
+    Address, Env, Vec,
+};
+use stellar_macros::only_admin;
+use stellar_tokens::fungible::Base;
+
+use crate::{
+    lock::ReentrancyLock, oracle::Pricing, read_reserved_usdc, strategy, timelock, Config, Context,
+    DataKey, Error, RUsdToken, RUsdTokenArgs, RUsdTokenClient,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CollateralInfo {
+    pub decimals: u32,
+    pub enabled: bool,
+    pub max_share_bps: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollateralSet {
+    #[topic]
+    pub asset: Address,
+    pub decimals: u32,
+    pub enabled: bool,
+    pub max_share_bps: u32,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    #[only_admin]
+    pub fn set_collateral(env: Env, asset: Address, enabled: bool, max_share_bps: u32) {
+        let _lock = ReentrancyLock::acquire(&env);
+        timelock::require_unlocked(&env);
+        apply_collateral(&env, asset, enabled, max_share_bps);
+    }
+
+    pub fn collaterals(env: Env) -> Vec<Address> {
+        read_collateral_list(&env)
+    }
+
+    pub fn collateral(env: Env, asset: Address) -> Option<CollateralInfo> {
+        try_read_collateral(&env, &asset)
+    }
+}
+
+pub(crate) fn apply_collateral(env: &Env, asset: Address, enabled: bool, max_share_bps: u32) {
+    if max_share_bps as i128 > BPS_DENOMINATOR {
+        panic_with_error!(env, Error::InvalidCollateralConfig);
+    }
+    let decimals = match try_read_collateral(env, &asset) {
+        Some(info) => info.decimals,
+        None => {
+            let decimals = TokenClient::new(env, &asset).decimals();
+            let mut assets = read_collateral_list(env);
+            assets.push_back(asset.clone());
+            env.storage()
+                .instance()
+                .set(&DataKey::CollateralList, &assets);
+            decimals
+        }
+    };
+    write_collateral(
+        env,
+        &asset,
+        &CollateralInfo {
+            decimals,
+            enabled,
+            max_share_bps,
+        },
+    );
+    CollateralSet {
+        asset,
+        decimals,
+        enabled,
+        max_share_bps,
+    }
+    .publish(env);
+}
+
+pub(crate) fn register_primary(env: &Env, asset: &Address, decimals: u32) {
+    let mut assets = Vec::new(env);
+    assets.push_back(asset.clone());
+    env.storage()
+        .instance()
+        .set(&DataKey::CollateralList, &assets);
+    write_collateral(
+        env,
+        asset,
+        &CollateralInfo {
+            decimals,
+            enabled: true,
+            max_share_bps: BPS_DENOMINATOR as u32,
+        },
+    );
+}
+
+fn read_collateral_list(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::CollateralList)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn try_read_collateral(env: &Env, asset: &Address) -> Option<CollateralInfo> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Collateral(asset.clone()))
+}
+
+fn write_collateral(env: &Env, asset: &Address, info: &CollateralInfo) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Collateral(asset.clone()), info);
+}
+
+pub(crate) fn is_registered(env: &Env, asset: &Address) -> bool {
+    env.storage()
+        .instance()
+        .has(&DataKey::Collateral(asset.clone()))
+}
+
+pub(crate) fn read_collateral(env: &Env, asset: &Address) -> CollateralInfo {
+    try_read_collateral(env, asset)
+        .unwrap_or_else(|| panic_with_error!(env, Error::UnsupportedCollateral))
+}
+
+pub(crate) fn read_enabled_collateral(env: &Env, asset: &Address) -> CollateralInfo {
+    let info = read_collateral(env, asset);
+    if !info.enabled {
+        panic_with_error!(env, Error::CollateralDisabled);
+    }
+    info
+}
+
+fn scale(env: &Env, from_decimals: u32, to_decimals: u32) -> (i128, i128) {
+    let pow = |exp: u32| {
+        10i128
+            .checked_pow(exp)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+    };
+    if from_decimals >= to_decimals {
+        (1, pow(from_decimals - to_decimals))
+    } else {
+        (pow(to_decimals - from_decimals), 1)
+    }
+}
+
+pub(crate) fn normalize(env: &Env, amount: i128, decimals: u32) -> i128 {
+    let (mul, div) = scale(env, decimals, Base::decimals(env));
+    amount
+        .checked_mul(mul)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+        / div
+}
+
+pub(crate) fn denormalize(env: &Env, amount: i128, decimals: u32) -> i128 {
+    let (mul, div) = scale(env, Base::decimals(env), decimals);
+    amount
+        .checked_mul(mul)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+        / div
+}
+
+fn read_normalized_balance(env: &Env, asset: &Address, decimals: u32) -> i128 {
+    let balance = TokenClient::new(env, asset).balance(&env.current_contract_address());
+    normalize(env, balance, decimals)
+}
+
+fn read_holdings(env: &Env, config: &Config, asset: &Address, decimals: u32) -> i128 {
+    let held = read_normalized_balance(env, asset, decimals);
+    if *asset != config.usdc_contract_id {
+        return held;
+    }
+    held.checked_sub(read_reserved_usdc(env))
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+        .max(0)
+        .checked_add(strategy::read_deployed(env))
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+}
+
+pub(crate) fn read_backing(env: &Env, config: &Config) -> i128 {
+    let pricing = Pricing::load(env);
+    let mut backing: i128 = 0;
+    for asset in read_collateral_list(env).iter() {
+        let info = read_collateral(env, &asset);
+        let held = read_holdings(env, config, &asset, info.decimals);
+        backing = backing
+            .checked_add(pricing.value_of(env, &asset, held))
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    }
+    backing
+}
+
+pub(crate) fn require_within_cap(env: &Env, ctx: &Context, pricing: &Pricing, asset: &Address) {
+    let info = read_collateral(env, asset);
+    if info.max_share_bps as i128 == BPS_DENOMINATOR || ctx.underlying == 0 {
+        return;
+    }
+    let held = read_holdings(env, &ctx.config, asset, info.decimals);
+    let held_bps = pricing
+        .value_of(env, asset, held)
+        .checked_mul(BPS_DENOMINATOR)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    let cap_bps = ctx
+        .underlying
+        .checked_mul(info.max_share_bps as i128)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    if held_bps > cap_bps {
+        panic_with_error!(env, Error::CollateralCapExceeded);
+    }
+}
@@ -0,0 +1,204 @@
+use soroban_sdk::{contractevent, contractimpl, panic_with_error, Address, Env};
+use stellar_macros::{only_admin, only_role};
+
+use crate::{
+    accounting, burn_shares, checkpoints, lock::ReentrancyLock, oracle::Pricing, queue,
+    require_positive_amount, strategy, usdc_client, Context, DataKey, Error, RUsdToken,
+    RUsdTokenArgs, RUsdTokenClient,
+};
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Freeze {
+    #[topic]
+    pub account: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Unfreeze {
+    #[topic]
+    pub account: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowlistSet {
+    #[topic]
+    pub account: Address,
+    pub allowed: bool,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowlistModeSet {
+    pub enabled: bool,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Clawback {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+    pub usdc_out: i128,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    #[only_role(operator, "compliance")]
+    pub fn freeze(env: Env, operator: Address, account: Address) {
+        let _lock = ReentrancyLock::acquire(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::Frozen(account.clone()), &true);
+        Freeze { account }.publish(&env);
+    }
+
+    #[only_role(operator, "compliance")]
+    pub fn unfreeze(env: Env, operator: Address, account: Address) {
+        let _lock = ReentrancyLock::acquire(&env);
+        env.storage()
+            .instance()
+            .remove(&DataKey::Frozen(account.clone()));
+        Unfreeze { account }.publish(&env);
+    }
+
+    pub fn frozen(env: Env, account: Address) -> bool {
+        is_frozen(&env, &account)
+    }
+
+    #[only_role(operator, "compliance")]
+    pub fn set_allowlisted(env: Env, operator: Address, account: Address, allowed: bool) {
+        let _lock = ReentrancyLock::acquire(&env);
+        let key = DataKey::Allowlisted(account.clone());
+        if allowed {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+        AllowlistSet { account, allowed }.publish(&env);
+    }
+
+    pub fn allowlisted(env: Env, account: Address) -> bool {
+        is_allowlisted(&env, &account)
+    }
+
+    #[only_admin]
+    pub fn set_allowlist_enabled(env: Env, enabled: bool) {
+        let _lock = ReentrancyLock::acquire(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::AllowlistEnabled, &enabled);
+        AllowlistModeSet { enabled }.publish(&env);
+    }
+
+    pub fn allowlist_enabled(env: Env) -> bool {
+        is_allowlist_enabled(&env)
+    }
+
+    #[only_admin]
+    pub fn set_clawback_recipient(env: Env, recipient: Address) {
+        let _lock = ReentrancyLock::acquire(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::ClawbackRecipient, &recipient);
+    }
+
+    pub fn clawback_recipient(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ClawbackRecipient)
+    }
+
+    #[only_role(operator, "compliance")]
+    pub fn clawback(env: Env, operator: Address, from: Address, amount: i128) {
+        let _lock = ReentrancyLock::acquire(&env);
+        require_positive_amount(&env, amount);
+        let recipient = read_clawback_recipient(&env);
+        let mut ctx = Context::load(&env);
+        let pricing = Pricing::load(&env);
+        accounting::mark_to_market(&env, &mut ctx, &pricing);
+        let rusd_out = burn_shares(&env, &mut ctx, &from, amount);
+        let usdc = usdc_client(&env, &ctx.config);
+        let usdc_out = pricing.units_for(&env, &usdc.address, rusd_out);
+        if usdc_out > 0 {
+            strategy::ensure_liquidity(&env, &ctx.config, usdc_out);
+            let contract = env.current_contract_address();
+            usdc.transfer(&contract, &recipient, &usdc_out);
+        }
+        ctx.release(&env, rusd_out);
+        accounting::write_total_assets(&env, ctx.underlying);
+        checkpoints::record(&env, &ctx);
+        Clawback {
+            from,
+            to: recipient,
+            amount,
+            usdc_out,
+        }
+        .publish(&env);
+    }
+
+    #[only_role(operator, "compliance")]
+    pub fn clawback_redeem(env: Env, operator: Address, request_id: u64) {
+        let _lock = ReentrancyLock::acquire(&env);
+        let recipient = read_clawback_recipient(&env);
+        let mut ctx = Context::load(&env);
+        let (owner, rusd_out, usdc_out) = queue::seize(&env, &mut ctx, request_id);
+        if usdc_out > 0 {
+            strategy::ensure_liquidity(&env, &ctx.config, usdc_out);
+            let contract = env.current_contract_address();
+            usdc_client(&env, &ctx.config).transfer(&contract, &recipient, &usdc_out);
+        }
+        if rusd_out > 0 {
+            accounting::write_total_assets(&env, ctx.underlying);
+            checkpoints::record(&env, &ctx);
+        }
+        Clawback {
+            from: owner,
+            to: recipient,
+            amount: rusd_out,
+            usdc_out,
+        }
+        .publish(&env);
+    }
+}
+
+fn read_clawback_recipient(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::ClawbackRecipient)
+        .unwrap_or_else(|| panic_with_error!(env, Error::ClawbackRecipientNotSet))
+}
+
+pub(crate) fn is_frozen(env: &Env, account: &Address) -> bool {
+    env.storage()
+        .instance()
+        .has(&DataKey::Frozen(account.clone()))
+}
+
+pub(crate) fn is_allowlist_enabled(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::AllowlistEnabled)
+        .unwrap_or(false)
+}
+
+pub(crate) fn is_allowlisted(env: &Env, account: &Address) -> bool {
+    env.storage()
+        .instance()
+        .has(&DataKey::Allowlisted(account.clone()))
+}
+
+pub(crate) fn require_not_frozen(env: &Env, account: &Address) {
+    if is_frozen(env, account) {
+        panic_with_error!(env, Error::AccountFrozen);
+    }
+}
+
+pub(crate) fn require_can_hold(env: &Env, account: &Address) {
+    require_not_frozen(env, account);
+    if is_allowlist_enabled(env) && !is_allowlisted(env, account) {
+        panic_with_error!(env, Error::NotAllowlisted);
+    }
+}
@@ -0,0 +1,84 @@
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env};
+use stellar_tokens::fungible::ContractOverrides;
+
+use crate::{
+    mul_div_floor, DataKey, Error, RUsdToken, RUsdTokenArgs, RUsdTokenClient, RebasingOverrides,
+};
+
+#[contractimpl]
+impl RUsdToken {
+    pub fn principal_of(env: Env, account: Address) -> i128 {
+        read_principal(&env, &account)
+    }
+
+    pub fn yield_earned(env: Env, account: Address) -> i128 {
+        RebasingOverrides::balance(&env, &account) - read_principal(&env, &account)
+    }
+
+    pub fn realized_yield(env: Env, account: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RealizedYield(account))
+            .unwrap_or(0)
+    }
+}
+
+fn read_principal(env: &Env, account: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Principal(account.clone()))
+        .unwrap_or(0)
+}
+
+fn write_principal(env: &Env, account: &Address, principal: i128) {
+    let key = DataKey::Principal(account.clone());
+    if principal == 0 {
+        env.storage().instance().remove(&key);
+    } else {
+        env.storage().instance().set(&key, &principal);
+    }
+}
+
+pub(crate) fn add_principal(env: &Env, account: &Address, amount: i128) {
+    let principal = read_principal(env, account)
+        .checked_add(amount)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    write_principal(env, account, principal);
+}
+
+pub(crate) fn remove_principal(
+    env: &Env,
+    account: &Address,
+    amount: i128,
+    shares_out: i128,
+    shares_before: i128,
+) {
+    let principal = read_principal(env, account);
+    let removed = if shares_out >= shares_before {
+        principal
+    } else {
+        mul_div_floor(env, principal, shares_out, shares_before)
+    };
+    write_principal(env, account, principal - removed);
+    let key = DataKey::RealizedYield(account.clone());
+    let realized: i128 = env.storage().instance().get(&key).unwrap_or(0);
+    let realized = realized
+        .checked_add(amount - removed)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    env.storage().instance().set(&key, &realized);
+}
+
+pub(crate) fn move_principal(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+    shares_out: i128,
+    shares_before: i128,
+) {
+    if from == to {
+        return;
+    }
+    remove_principal(env, from, amount, shares_out, shares_before);
+    add_principal(env, to, amount);
+}
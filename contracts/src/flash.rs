@@ -0,0 +1,102 @@
+use rusd_math::BPS_DENOMINATOR;
+use soroban_sdk::{
+    contractclient, contractevent, contractimpl, panic_with_error, Address, Bytes, Env,
+};
+use stellar_macros::only_admin;
+
+use crate::{
+    accounting, checkpoints, lock::ReentrancyLock, mul_div_ceil, oracle::Pricing, read_liquidity,
+    require_positive_amount, timelock, usdc_client, Context, DataKey, Error, RUsdToken,
+    RUsdTokenArgs, RUsdTokenClient,
+};
+
+#[contractclient(name = "FlashLoanReceiverClient")]
+pub trait FlashLoanReceiver {
+    fn on_flash_loan(env: Env, token: Address, amount: i128, fee: i128, data: Bytes);
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlashLoan {
+    #[topic]
+    pub receiver: Address,
+    pub amount: i128,
+    pub fee: i128,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    pub fn flash_loan(env: Env, receiver: Address, amount: i128, data: Bytes) {
+        let _lock = ReentrancyLock::acquire(&env);
+        receiver.require_auth();
+        require_positive_amount(&env, amount);
+        let mut ctx = Context::load(&env);
+        if amount > read_liquidity(&env, &ctx.config) {
+            panic_with_error!(&env, Error::InsufficientLiquidity);
+        }
+        let fee = flash_fee_for(&env, amount);
+        let usdc = usdc_client(&env, &ctx.config);
+        let contract = env.current_contract_address();
+        let before = usdc.balance(&contract);
+        usdc.transfer(&contract, &receiver, &amount);
+        FlashLoanReceiverClient::new(&env, &receiver).on_flash_loan(
+            &usdc.address,
+            &amount,
+            &fee,
+            &data,
+        );
+        if usdc.balance(&contract) < before + fee {
+            panic_with_error!(&env, Error::FlashLoanNotRepaid);
+        }
+        if fee > 0 {
+            ctx.underlying += Pricing::load(&env).value_of(&env, &usdc.address, fee);
+            accounting::write_total_assets(&env, ctx.underlying);
+            checkpoints::record(&env, &ctx);
+        }
+        FlashLoan {
+            receiver,
+            amount,
+            fee,
+        }
+        .publish(&env);
+    }
+
+    pub fn flash_fee(env: Env, amount: i128) -> i128 {
+        flash_fee_for(&env, amount)
+    }
+
+    #[only_admin]
+    pub fn set_flash_fee_bps(env: Env, bps: u32) {
+        let _lock = ReentrancyLock::acquire(&env);
+        timelock::require_unlocked(&env);
+        apply_flash_fee_bps(&env, bps);
+    }
+
+    pub fn flash_fee_bps(env: Env) -> u32 {
+        read_flash_fee_bps(&env)
+    }
+}
+
+pub(crate) fn apply_flash_fee_bps(env: &Env, bps: u32) {
+    if bps as i128 > BPS_DENOMINATOR {
+        panic_with_error!(env, Error::InvalidFlashFee);
+    }
+    env.storage().instance().set(&DataKey::FlashFeeBps, &bps);
+}
+
+fn read_flash_fee_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::FlashFeeBps)
+        .unwrap_or(0)
+}
+
+// Rounded up so that small loans cannot dodge a non-zero fee.
+fn flash_fee_for(env: &Env, amount: i128) -> i128 {
+    mul_div_ceil(
+        env,
+        amount,
+        read_flash_fee_bps(env) as i128,
+        BPS_DENOMINATOR,
+    )
+}
@@ -1,21 +1,108 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, panic_with_error,
-    token::Client as TokenClient, Address, Env, String,
+    contract, contracterror, contractevent, contractimpl, contracttype, panic_with_error,
+    token::Client as TokenClient, Address, Env, String, Symbol,
 };
+use stellar_access::access_control::{self as access_control, AccessControl};
 use stellar_macros::default_impl;
-use stellar_tokens::fungible::{emit_mint, emit_transfer, Base, FungibleToken};
+use lock::ReentrancyLock;
+use rusd_math::MathError;
+use stellar_tokens::fungible::{
+    burnable::emit_burn, emit_mint, emit_transfer, Base, FungibleToken,
+};
+
+mod accounting;
+mod batch;
+mod checkpoints;
+pub mod collateral;
+mod compliance;
+mod cost_basis;
+pub mod flash;
+mod limits;
+mod lock;
+pub mod oracle;
+mod queue;
+mod rescue;
+pub mod strategy;
+pub mod testutils;
+pub mod timelock;
 
 #[contracterror]
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Error {
+    /// Reserved so later codes keep their values. Initialization happens in
+    /// `__constructor`, which runs exactly once, so this is never returned.
     AlreadyInitialized = 1,
     NotInitialized = 2,
     ZeroAmount = 3,
     InsufficientShares = 4,
     DivisionByZero = 6,
     Overflow = 7,
+    AccountFrozen = 8,
+    NotAllowlisted = 9,
+    ClawbackRecipientNotSet = 10,
+    RequestNotFound = 11,
+    NothingToClaim = 12,
+    InvalidBufferTarget = 13,
+    YieldVaultNotEmpty = 14,
+    YieldVaultNotSet = 15,
+    WithdrawalLimitExceeded = 16,
+    InvalidWithdrawalLimit = 17,
+    UnsupportedCollateral = 18,
+    CollateralDisabled = 19,
+    CollateralCapExceeded = 20,
+    CollateralUnavailable = 21,
+    InvalidCollateralConfig = 22,
+    InvalidOracleConfig = 23,
+    OraclePriceUnavailable = 24,
+    OraclePriceStale = 25,
+    CollateralDepegged = 26,
+    BatchTooLarge = 27,
+    ProtectedToken = 28,
+    TimelockActive = 29,
+    TimelockNotSet = 30,
+    OperationNotFound = 31,
+    OperationNotReady = 32,
+    OperationExpired = 33,
+    InvalidTimelockConfig = 34,
+    InvalidUnderlying = 35,
+    DecimalsMismatch = 36,
+    UnderlyingNotReceived = 37,
+    InsufficientLiquidity = 38,
+    FlashLoanNotRepaid = 39,
+    InvalidFlashFee = 40,
+    /// Defence in depth: the host rejects callbacks into the token before the
+    /// lock is reached, so no callback returns this under the current host.
+    Reentrant = 41,
+}
+
+// Share movements behind the SEP-41 events, so indexers can rebuild share
+// balances. Queued redemptions are held under the contract's own address.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SharesMinted {
+    #[topic]
+    pub to: Address,
+    pub shares: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SharesBurned {
+    #[topic]
+    pub from: Address,
+    pub shares: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SharesTransferred {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub shares: i128,
 }
 
 #[derive(Clone)]
@@ -30,6 +117,34 @@ pub enum DataKey {
     Config,
     TotalShares,
     Shares(Address),
+    Frozen(Address),
+    AllowlistEnabled,
+    Allowlisted(Address),
+    ClawbackRecipient,
+    ReservedUsdc,
+    QueuedShares,
+    QueueHead,
+    QueueTail,
+    RedeemRequest(u64),
+    YieldVault,
+    BufferTargetBps,
+    WithdrawalLimit,
+    OutflowBuckets,
+    CollateralList,
+    Collateral(Address),
+    Oracle,
+    CheckpointCount,
+    Checkpoint(u32),
+    Principal(Address),
+    RealizedYield(Address),
+    TotalAssets,
+    YieldDrip,
+    DripPeriod,
+    Timelock,
+    TimelockNextId,
+    TimelockOperation(u64),
+    FlashFeeBps,
+    Locked,
 }
 
 #[contract]
@@ -39,34 +154,28 @@ pub struct RebasingOverrides;
 
 impl stellar_tokens::fungible::ContractOverrides for RebasingOverrides {
     fn total_supply(e: &Env) -> i128 {
-        let total_shares = read_total_shares(e);
-        if total_shares == 0 {
-            return 0;
-        }
-        let underlying = read_underlying(e);
-        rusd_from_shares(e, total_shares, total_shares, underlying)
+        let ctx = Context::load(e);
+        ctx.rusd_from_shares(e, ctx.total_shares)
     }
 
     fn balance(e: &Env, account: &Address) -> i128 {
-        let total_shares = read_total_shares(e);
-        if total_shares == 0 {
-            return 0;
-        }
         let shares = read_shares(e, account);
         if shares == 0 {
             return 0;
         }
-        let underlying = read_underlying(e);
-        rusd_from_shares(e, shares, total_shares, underlying)
+        let ctx = Context::load(e);
+        ctx.rusd_from_shares(e, shares)
     }
 
     fn transfer(e: &Env, from: &Address, to: &Address, amount: i128) {
+        let _lock = ReentrancyLock::acquire(e);
         require_positive_amount(e, amount);
         from.require_auth();
+        compliance::require_can_hold(e, from);
+        compliance::require_can_hold(e, to);
 
-        let total_shares = read_total_shares(e);
-        let underlying = read_underlying(e);
-        let shares_to_move = shares_from_rusd(e, amount, total_shares, underlying);
+        let ctx = Context::load(e);
+        let shares_to_move = ctx.shares_from_rusd(e, amount);
         let from_shares = read_shares(e, from);
         if from_shares < shares_to_move {
             panic_with_error!(e, Error::InsufficientShares);
@@ -80,16 +189,26 @@ impl stellar_tokens::fungible::ContractOverrides for RebasingOverrides {
             .checked_add(shares_to_move)
             .unwrap_or_else(|| panic_with_error!(e, Error::Overflow));
         write_shares(e, to, new_to_shares);
+        cost_basis::move_principal(e, from, to, amount, shares_to_move, from_shares);
         emit_transfer(e, from, to, amount);
+        SharesTransferred {
+            from: from.clone(),
+            to: to.clone(),
+            shares: shares_to_move,
+        }
+        .publish(e);
     }
 
     fn transfer_from(e: &Env, spender: &Address, from: &Address, to: &Address, amount: i128) {
+        let _lock = ReentrancyLock::acquire(e);
         require_positive_amount(e, amount);
         spender.require_auth();
+        compliance::require_not_frozen(e, spender);
+        compliance::require_can_hold(e, from);
+        compliance::require_can_hold(e, to);
         Base::spend_allowance(e, from, spender, amount);
-        let total_shares = read_total_shares(e);
-        let underlying = read_underlying(e);
-        let shares_to_move = shares_from_rusd(e, amount, total_shares, underlying);
+        let ctx = Context::load(e);
+        let shares_to_move = ctx.shares_from_rusd(e, amount);
         let from_shares = read_shares(e, from);
         if from_shares < shares_to_move {
             panic_with_error!(e, Error::InsufficientShares);
@@ -103,7 +222,63 @@ impl stellar_tokens::fungible::ContractOverrides for RebasingOverrides {
             .checked_add(shares_to_move)
             .unwrap_or_else(|| panic_with_error!(e, Error::Overflow));
         write_shares(e, to, new_to_shares);
+        cost_basis::move_principal(e, from, to, amount, shares_to_move, from_shares);
         emit_transfer(e, from, to, amount);
+        SharesTransferred {
+            from: from.clone(),
+            to: to.clone(),
+            shares: shares_to_move,
+        }
+        .publish(e);
+    }
+
+    fn approve(e: &Env, owner: &Address, spender: &Address, amount: i128, live_until_ledger: u32) {
+        let _lock = ReentrancyLock::acquire(e);
+        compliance::require_not_frozen(e, owner);
+        Base::approve(e, owner, spender, amount, live_until_ledger);
+    }
+}
+
+pub(crate) struct Context {
+    pub config: Config,
+    pub total_shares: i128,
+    pub underlying: i128,
+}
+
+impl Context {
+    pub(crate) fn load(env: &Env) -> Self {
+        let config = read_config(env);
+        let total_shares = read_total_shares(env);
+        let underlying = accounting::read_total_assets(env);
+        Context {
+            config,
+            total_shares,
+            underlying,
+        }
+    }
+
+    pub(crate) fn shares_from_rusd(&self, env: &Env, amount: i128) -> i128 {
+        rusd_math::shares_from_rusd(amount, self.total_shares, self.underlying)
+            .unwrap_or_else(|err| math_error(env, err))
+    }
+
+    pub(crate) fn rusd_from_shares(&self, env: &Env, shares: i128) -> i128 {
+        rusd_math::rusd_from_shares(shares, self.total_shares, self.underlying)
+            .unwrap_or_else(|err| math_error(env, err))
+    }
+
+    // Accounted assets can drift from real balances between syncs, so a
+    // payout larger than them fails instead of wrapping below zero.
+    pub(crate) fn release(&mut self, env: &Env, value: i128) {
+        self.underlying = self
+            .underlying
+            .checked_sub(value)
+            .filter(|underlying| *underlying >= 0)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    }
+
+    pub(crate) fn is_primary(&self, asset: &Address) -> bool {
+        *asset == self.config.usdc_contract_id
     }
 }
 
@@ -112,66 +287,57 @@ mod test;
 
 #[contractimpl]
 impl RUsdToken {
-    pub fn init(env: Env, usdc_contract_id: Address, name: String, symbol: String, decimals: u32) {
-        if env.storage().instance().has(&DataKey::Config) {
-            panic_with_error!(&env, Error::AlreadyInitialized);
+    // Runs as part of deployment, so there is no window in which another
+    // account could initialize the instance first.
+    pub fn __constructor(
+        env: Env,
+        admin: Address,
+        usdc_contract_id: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
+    ) {
+        admin.require_auth();
+        if read_underlying_decimals(&env, &usdc_contract_id) != decimals {
+            panic_with_error!(&env, Error::DecimalsMismatch);
         }
+        collateral::register_primary(&env, &usdc_contract_id, decimals);
         let config = Config { usdc_contract_id };
         env.storage().instance().set(&DataKey::Config, &config);
+        access_control::set_admin(&env, &admin);
         Base::set_metadata(&env, decimals, name, symbol);
     }
 
     pub fn mint(env: Env, to: Address, amount: i128) {
-        require_positive_amount(&env, amount);
-        to.require_auth();
-        let contract = env.current_contract_address();
-        let usdc = usdc_client(&env);
-        usdc.transfer_from(&contract, &to, &contract, &amount);
-        let total_shares = read_total_shares(&env);
-        let underlying_after = read_underlying(&env);
-        let shares_to_mint = if total_shares == 0 {
-            amount
-        } else {
-            let underlying_before = underlying_after
-                .checked_sub(amount)
-                .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
-            shares_from_rusd(&env, amount, total_shares, underlying_before)
-        };
-        let user_shares = read_shares(&env, &to);
-        let new_user_shares = user_shares
-            .checked_add(shares_to_mint)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
-        write_shares(&env, &to, new_user_shares);
-        let new_total_shares = total_shares
-            .checked_add(shares_to_mint)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
-        write_total_shares(&env, new_total_shares);
-
-        emit_mint(&env, &to, amount);
+        let _lock = ReentrancyLock::acquire(&env);
+        let ctx = Context::load(&env);
+        let usdc_contract_id = ctx.config.usdc_contract_id.clone();
+        mint_with_asset(&env, ctx, &to, &usdc_contract_id, amount);
+    }
+
+    pub fn mint_with(env: Env, to: Address, asset: Address, amount: i128) {
+        let _lock = ReentrancyLock::acquire(&env);
+        mint_with_asset(&env, Context::load(&env), &to, &asset, amount);
     }
 
     pub fn burn(env: Env, from: Address, amount: i128) {
-        require_positive_amount(&env, amount);
-        from.require_auth();
-        let total_shares = read_total_shares(&env);
-        let underlying = read_underlying(&env);
-        let shares_to_burn = shares_from_rusd(&env, amount, total_shares, underlying);
-        let user_shares = read_shares(&env, &from);
-        if user_shares < shares_to_burn {
-            panic_with_error!(&env, Error::InsufficientShares);
-        }
-        let usdc_out = rusd_from_shares(&env, shares_to_burn, total_shares, underlying);
-        let new_user_shares = user_shares
-            .checked_sub(shares_to_burn)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
-        write_shares(&env, &from, new_user_shares);
-        let new_total_shares = total_shares
-            .checked_sub(shares_to_burn)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
-        write_total_shares(&env, new_total_shares);
-        let contract = env.current_contract_address();
-        let usdc = usdc_client(&env);
-        usdc.transfer(&contract, &from, &usdc_out);
+        let _lock = ReentrancyLock::acquire(&env);
+        let ctx = Context::load(&env);
+        let usdc_contract_id = ctx.config.usdc_contract_id.clone();
+        burn_to_asset(&env, ctx, &from, amount, &usdc_contract_id);
+    }
+
+    pub fn burn_to(env: Env, from: Address, amount: i128, asset: Address) {
+        let _lock = ReentrancyLock::acquire(&env);
+        burn_to_asset(&env, Context::load(&env), &from, amount, &asset);
+    }
+
+    pub fn shares_of(env: Env, account: Address) -> i128 {
+        read_shares(&env, &account)
+    }
+
+    pub fn total_shares(env: Env) -> i128 {
+        read_total_shares(&env)
     }
 }
 
@@ -181,6 +347,152 @@ impl FungibleToken for RUsdToken {
     type ContractType = RebasingOverrides;
 }
 
+// The role views come from `default_impl`; the changes take the lock like
+// every other state-changing entrypoint.
+#[default_impl]
+#[contractimpl]
+impl AccessControl for RUsdToken {
+    fn grant_role(e: &Env, caller: Address, account: Address, role: Symbol) {
+        let _lock = ReentrancyLock::acquire(e);
+        access_control::grant_role(e, &caller, &account, &role);
+    }
+
+    fn revoke_role(e: &Env, caller: Address, account: Address, role: Symbol) {
+        let _lock = ReentrancyLock::acquire(e);
+        access_control::revoke_role(e, &caller, &account, &role);
+    }
+
+    fn renounce_role(e: &Env, caller: Address, role: Symbol) {
+        let _lock = ReentrancyLock::acquire(e);
+        access_control::renounce_role(e, &caller, &role);
+    }
+
+    fn transfer_admin_role(e: &Env, new_admin: Address, live_until_ledger: u32) {
+        let _lock = ReentrancyLock::acquire(e);
+        access_control::transfer_admin_role(e, &new_admin, live_until_ledger);
+    }
+
+    fn accept_admin_transfer(e: &Env) {
+        let _lock = ReentrancyLock::acquire(e);
+        access_control::accept_admin_transfer(e);
+    }
+
+    fn set_role_admin(e: &Env, role: Symbol, admin_role: Symbol) {
+        let _lock = ReentrancyLock::acquire(e);
+        access_control::set_role_admin(e, &role, &admin_role);
+    }
+
+    fn renounce_admin(e: &Env) {
+        let _lock = ReentrancyLock::acquire(e);
+        access_control::renounce_admin(e);
+    }
+}
+
+fn mint_with_asset(env: &Env, mut ctx: Context, to: &Address, asset: &Address, amount: i128) {
+    require_positive_amount(env, amount);
+    to.require_auth();
+    compliance::require_can_hold(env, to);
+    let info = collateral::read_enabled_collateral(env, asset);
+    let pricing = oracle::Pricing::load(env);
+    pricing.require_pegged(env, asset);
+    let contract = env.current_contract_address();
+    let token = TokenClient::new(env, asset);
+    let balance_before = token.balance(&contract);
+    token.transfer_from(&contract, to, &contract, &amount);
+    let received = (token.balance(&contract) - balance_before).min(amount);
+    if received <= 0 {
+        panic_with_error!(env, Error::UnderlyingNotReceived);
+    }
+    let normalized = collateral::normalize(env, received, info.decimals);
+    let rusd_amount = pricing.value_of(env, asset, normalized);
+    require_positive_amount(env, rusd_amount);
+    let shares_to_mint = ctx.shares_from_rusd(env, rusd_amount);
+    let user_shares = read_shares(env, to);
+    let new_user_shares = user_shares
+        .checked_add(shares_to_mint)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    write_shares(env, to, new_user_shares);
+    ctx.total_shares = ctx
+        .total_shares
+        .checked_add(shares_to_mint)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    write_total_shares(env, ctx.total_shares);
+    ctx.underlying = ctx
+        .underlying
+        .checked_add(rusd_amount)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    accounting::write_total_assets(env, ctx.underlying);
+    cost_basis::add_principal(env, to, rusd_amount);
+    collateral::require_within_cap(env, &ctx, &pricing, asset);
+    limits::record_inflow(env, rusd_amount);
+    if ctx.is_primary(asset) {
+        strategy::deploy_excess(env, &ctx);
+    }
+    checkpoints::record(env, &ctx);
+
+    emit_mint(env, to, rusd_amount);
+    SharesMinted {
+        to: to.clone(),
+        shares: shares_to_mint,
+    }
+    .publish(env);
+}
+
+fn burn_to_asset(env: &Env, mut ctx: Context, from: &Address, amount: i128, asset: &Address) {
+    require_positive_amount(env, amount);
+    from.require_auth();
+    compliance::require_can_hold(env, from);
+    let info = collateral::read_collateral(env, asset);
+    let pricing = oracle::Pricing::load(env);
+    accounting::mark_to_market(env, &mut ctx, &pricing);
+    let usdc_out = burn_shares(env, &mut ctx, from, amount);
+    limits::record_outflow(env, &ctx, usdc_out);
+    let contract = env.current_contract_address();
+    let token = TokenClient::new(env, asset);
+    let units_out = pricing.units_for(env, asset, usdc_out);
+    let available = if ctx.is_primary(asset) {
+        strategy::ensure_liquidity(env, &ctx.config, units_out)
+    } else {
+        collateral::normalize(env, token.balance(&contract), info.decimals)
+    };
+    if units_out > available {
+        panic_with_error!(env, Error::CollateralUnavailable);
+    }
+    let amount_out = collateral::denormalize(env, units_out, info.decimals);
+    if amount_out > 0 {
+        token.transfer(&contract, from, &amount_out);
+    }
+    ctx.release(env, usdc_out);
+    accounting::write_total_assets(env, ctx.underlying);
+    checkpoints::record(env, &ctx);
+    emit_burn(env, from, amount);
+}
+
+fn burn_shares(env: &Env, ctx: &mut Context, from: &Address, amount: i128) -> i128 {
+    let shares_to_burn = ctx.shares_from_rusd(env, amount);
+    let user_shares = read_shares(env, from);
+    if user_shares < shares_to_burn {
+        panic_with_error!(env, Error::InsufficientShares);
+    }
+    let usdc_out = ctx.rusd_from_shares(env, shares_to_burn);
+    cost_basis::remove_principal(env, from, amount, shares_to_burn, user_shares);
+    let new_user_shares = user_shares
+        .checked_sub(shares_to_burn)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    write_shares(env, from, new_user_shares);
+    ctx.total_shares = ctx
+        .total_shares
+        .checked_sub(shares_to_burn)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    write_total_shares(env, ctx.total_shares);
+    SharesBurned {
+        from: from.clone(),
+        shares: shares_to_burn,
+    }
+    .publish(env);
+    usdc_out
+}
+
 fn read_config(env: &Env) -> Config {
     env.storage().instance().get(&DataKey::Config)
         .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
@@ -207,61 +519,61 @@ fn write_shares(env: &Env, owner: &Address, shares: i128) {
     }
 }
 
-fn usdc_client(env: &Env) -> TokenClient<'_> {
-    let config = read_config(env);
+fn usdc_client<'a>(env: &'a Env, config: &Config) -> TokenClient<'a> {
     TokenClient::new(env, &config.usdc_contract_id)
 }
 
-fn read_underlying(env: &Env) -> i128 {
+fn read_underlying_decimals(env: &Env, asset: &Address) -> u32 {
+    let token = TokenClient::new(env, asset);
+    let contract = env.current_contract_address();
+    match (token.try_decimals(), token.try_balance(&contract)) {
+        (Ok(Ok(decimals)), Ok(Ok(_))) => decimals,
+        _ => panic_with_error!(env, Error::InvalidUnderlying),
+    }
+}
+
+fn read_idle_usdc(env: &Env, config: &Config) -> i128 {
     let contract = env.current_contract_address();
-    let usdc = usdc_client(env);
+    let usdc = usdc_client(env, config);
     usdc.balance(&contract)
 }
 
+fn read_reserved_usdc(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::ReservedUsdc).unwrap_or(0)
+}
+
+fn write_reserved_usdc(env: &Env, reserved: i128) {
+    env.storage().instance().set(&DataKey::ReservedUsdc, &reserved);
+}
+
+fn read_liquidity(env: &Env, config: &Config) -> i128 {
+    let idle = read_idle_usdc(env, config);
+    idle.checked_sub(read_reserved_usdc(env))
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+        .max(0)
+}
+
 fn require_positive_amount(env: &Env, amount: i128) {
     if amount <= 0 {
         panic_with_error!(env, Error::ZeroAmount);
     }
 }
 
-fn shares_from_rusd(env: &Env, amount: i128, total_shares: i128, underlying: i128) -> i128 {
-    if total_shares == 0 {
-        return amount;
-    }
-    if underlying == 0 {
-        panic_with_error!(env, Error::DivisionByZero);
-    }
-    mul_div_ceil(env, amount, total_shares, underlying)
+fn mul_div_floor(env: &Env, a: i128, b: i128, denom: i128) -> i128 {
+    rusd_math::mul_div_floor(a, b, denom).unwrap_or_else(|err| math_error(env, err))
 }
 
-fn rusd_from_shares(env: &Env, shares: i128, total_shares: i128, underlying: i128) -> i128 {
-    if total_shares == 0 || shares == 0 {
-        return 0;
-    }
-    mul_div_floor(env, shares, underlying, total_shares)
+fn mul_div_ceil(env: &Env, a: i128, b: i128, denom: i128) -> i128 {
+    rusd_math::mul_div_ceil(a, b, denom).unwrap_or_else(|err| math_error(env, err))
 }
 
-fn mul_div_floor(env: &Env, a: i128, b: i128, denom: i128) -> i128 {
-    if denom == 0 {
-        panic_with_error!(env, Error::DivisionByZero);
-    }
-    let prod = a
-        .checked_mul(b)
-        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
-    prod / denom
+fn bps_of(env: &Env, amount: i128, bps: u32) -> i128 {
+    rusd_math::bps_of(amount, bps).unwrap_or_else(|err| math_error(env, err))
 }
 
-fn mul_div_ceil(env: &Env, a: i128, b: i128, denom: i128) -> i128 {
-    if denom == 0 {
-        panic_with_error!(env, Error::DivisionByZero);
-    }
-    let prod = a
-        .checked_mul(b)
-        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
-    let div = prod / denom;
-    if prod % denom == 0 {
-        div
-    } else {
-        div + 1
+fn math_error(env: &Env, err: MathError) -> ! {
+    match err {
+        MathError::DivisionByZero => panic_with_error!(env, Error::DivisionByZero),
+        MathError::Overflow => panic_with_error!(env, Error::Overflow),
     }
 }
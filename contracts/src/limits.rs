@@ -0,0 +1,157 @@
+use rusd_math::BPS_DENOMINATOR;
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Env, Vec};
+use stellar_macros::only_admin;
+
+use crate::{
+    bps_of, lock::ReentrancyLock, timelock, Context, DataKey, Error, RUsdToken, RUsdTokenArgs,
+    RUsdTokenClient,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct WithdrawalLimit {
+    pub max_bps: u32,
+    pub window: u64,
+}
+
+// Net outflow within one slot of `window / OUTFLOW_SLOTS` seconds.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct OutflowBucket {
+    pub slot: u64,
+    pub net_outflow: i128,
+}
+
+// The window rolls in slots. Every slot that overlaps the last `window`
+// seconds still counts, so no interval of `window` seconds can see more than
+// `max_bps` go out, at the cost of capacity returning up to one slot late.
+const OUTFLOW_SLOTS: u64 = 24;
+
+#[contractimpl]
+impl RUsdToken {
+    #[only_admin]
+    pub fn set_withdrawal_limit(env: Env, limit: Option<WithdrawalLimit>) {
+        let _lock = ReentrancyLock::acquire(&env);
+        timelock::require_unlocked(&env);
+        apply_withdrawal_limit(&env, limit);
+    }
+
+    pub fn withdrawal_limit(env: Env) -> Option<WithdrawalLimit> {
+        read_limit(&env)
+    }
+
+    pub fn withdrawal_capacity(env: Env) -> Option<i128> {
+        remaining_capacity(&env, &Context::load(&env))
+    }
+}
+
+pub(crate) fn apply_withdrawal_limit(env: &Env, limit: Option<WithdrawalLimit>) {
+    match limit {
+        Some(limit) => {
+            if limit.max_bps as i128 > BPS_DENOMINATOR || limit.window == 0 {
+                panic_with_error!(env, Error::InvalidWithdrawalLimit);
+            }
+            env.storage()
+                .instance()
+                .set(&DataKey::WithdrawalLimit, &limit);
+        }
+        None => env.storage().instance().remove(&DataKey::WithdrawalLimit),
+    }
+    env.storage().instance().remove(&DataKey::OutflowBuckets);
+}
+
+fn read_limit(env: &Env) -> Option<WithdrawalLimit> {
+    env.storage().instance().get(&DataKey::WithdrawalLimit)
+}
+
+struct Window {
+    slot: u64,
+    buckets: Vec<OutflowBucket>,
+}
+
+impl Window {
+    fn net_outflow(&self) -> i128 {
+        self.buckets
+            .iter()
+            .map(|bucket| bucket.net_outflow)
+            .sum()
+    }
+
+    fn add(&mut self, amount: i128) {
+        let last = self.buckets.len().checked_sub(1);
+        match last.and_then(|index| Some((index, self.buckets.get(index)?))) {
+            Some((index, mut bucket)) if bucket.slot == self.slot => {
+                bucket.net_outflow += amount;
+                self.buckets.set(index, bucket);
+            }
+            _ => self.buckets.push_back(OutflowBucket {
+                slot: self.slot,
+                net_outflow: amount,
+            }),
+        }
+    }
+
+    fn write(&self, env: &Env) {
+        env.storage()
+            .instance()
+            .set(&DataKey::OutflowBuckets, &self.buckets);
+    }
+}
+
+fn current_window(env: &Env, limit: &WithdrawalLimit) -> Window {
+    let slot_seconds = (limit.window / OUTFLOW_SLOTS).max(1);
+    let slot = env.ledger().timestamp() / slot_seconds;
+    let oldest = slot.saturating_sub(limit.window.div_ceil(slot_seconds));
+    let stored: Vec<OutflowBucket> = env
+        .storage()
+        .instance()
+        .get(&DataKey::OutflowBuckets)
+        .unwrap_or_else(|| Vec::new(env));
+    let mut buckets = Vec::new(env);
+    for bucket in stored.iter().filter(|bucket| bucket.slot >= oldest) {
+        buckets.push_back(bucket);
+    }
+    Window { slot, buckets }
+}
+
+// The cap is a share of the assets before the window's flows, so outflows
+// already counted do not shrink it further.
+fn window_capacity(env: &Env, ctx: &Context, limit: &WithdrawalLimit, window: &Window) -> i128 {
+    let net_outflow = window.net_outflow();
+    let base_assets = ctx
+        .underlying
+        .checked_add(net_outflow.max(0))
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+    let max_outflow = bps_of(env, base_assets, limit.max_bps);
+    (max_outflow - net_outflow).max(0)
+}
+
+pub(crate) fn remaining_capacity(env: &Env, ctx: &Context) -> Option<i128> {
+    let limit = read_limit(env)?;
+    let window = current_window(env, &limit);
+    Some(window_capacity(env, ctx, &limit, &window))
+}
+
+pub(crate) fn record_outflow(env: &Env, ctx: &Context, amount: i128) {
+    let Some(limit) = read_limit(env) else {
+        return;
+    };
+    let mut window = current_window(env, &limit);
+    if amount > window_capacity(env, ctx, &limit, &window) {
+        panic_with_error!(env, Error::WithdrawalLimitExceeded);
+    }
+    window.add(amount);
+    window.write(env);
+}
+
+pub(crate) fn record_inflow(env: &Env, amount: i128) {
+    let Some(limit) = read_limit(env) else {
+        return;
+    };
+    let mut window = current_window(env, &limit);
+    if window.buckets.is_empty() {
+        return;
+    }
+    window.add(-amount);
+    window.write(env);
+}
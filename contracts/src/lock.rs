@@ -0,0 +1,34 @@
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::{DataKey, Error};
+
+// Held for the duration of a state-changing entrypoint and released when
+// dropped. A panic rolls the whole invocation back, lock included.
+//
+// The host already rejects re-entry into a contract on the call stack with
+// `Error(Context, InvalidAction)`, so under the current host `Reentrant` is
+// unreachable through a callback. The lock is defence in depth: it keeps the
+// guarantee in the contract if that host policy is ever relaxed.
+pub(crate) struct ReentrancyLock<'a> {
+    env: &'a Env,
+}
+
+impl<'a> ReentrancyLock<'a> {
+    pub(crate) fn acquire(env: &'a Env) -> Self {
+        if is_locked(env) {
+            panic_with_error!(env, Error::Reentrant);
+        }
+        env.storage().instance().set(&DataKey::Locked, &true);
+        ReentrancyLock { env }
+    }
+}
+
+impl Drop for ReentrancyLock<'_> {
+    fn drop(&mut self) {
+        self.env.storage().instance().remove(&DataKey::Locked);
+    }
+}
+
+pub(crate) fn is_locked(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Locked)
+}
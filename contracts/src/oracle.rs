@@ -0,0 +1,133 @@
+use rusd_math::BPS_DENOMINATOR;
+use soroban_sdk::{
+    contractclient, contractimpl, contracttype, panic_with_error, Address, Env, Symbol,
+};
+use stellar_macros::only_admin;
+
+use crate::{
+    lock::ReentrancyLock, mul_div_floor, timelock, DataKey, Error, RUsdToken, RUsdTokenArgs,
+    RUsdTokenClient,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+#[contractclient(name = "PriceFeedClient")]
+pub trait PriceFeed {
+    fn decimals(env: Env) -> u32;
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct OracleConfig {
+    pub oracle: Address,
+    pub max_age: u64,
+    pub max_deviation_bps: u32,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    #[only_admin]
+    pub fn set_oracle(env: Env, config: Option<OracleConfig>) {
+        let _lock = ReentrancyLock::acquire(&env);
+        timelock::require_unlocked(&env);
+        apply_oracle(&env, config);
+    }
+
+    pub fn oracle(env: Env) -> Option<OracleConfig> {
+        read_oracle(&env)
+    }
+}
+
+pub(crate) fn apply_oracle(env: &Env, config: Option<OracleConfig>) {
+    match config {
+        Some(config) => {
+            if config.max_age == 0 || config.max_deviation_bps as i128 > BPS_DENOMINATOR {
+                panic_with_error!(env, Error::InvalidOracleConfig);
+            }
+            env.storage().instance().set(&DataKey::Oracle, &config);
+        }
+        None => env.storage().instance().remove(&DataKey::Oracle),
+    }
+}
+
+fn read_oracle(env: &Env) -> Option<OracleConfig> {
+    env.storage().instance().get(&DataKey::Oracle)
+}
+
+pub(crate) struct Pricing {
+    feed: Option<(OracleConfig, PriceFeedClient<'static>, i128)>,
+}
+
+impl Pricing {
+    pub(crate) fn load(env: &Env) -> Self {
+        let feed = read_oracle(env).map(|config| {
+            let client = PriceFeedClient::new(env, &config.oracle);
+            let one = 10i128
+                .checked_pow(client.decimals())
+                .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+            (config, client, one)
+        });
+        Pricing { feed }
+    }
+
+    pub(crate) fn is_live(&self) -> bool {
+        self.feed.is_some()
+    }
+
+    fn backing_price(&self, env: &Env, asset: &Address) -> (i128, i128) {
+        match &self.feed {
+            Some((_, client, one)) => {
+                let data = read_price(env, client, asset);
+                (data.price.min(*one), *one)
+            }
+            None => (1, 1),
+        }
+    }
+
+    pub(crate) fn require_pegged(&self, env: &Env, asset: &Address) {
+        let Some((config, client, one)) = &self.feed else {
+            return;
+        };
+        let data = read_price(env, client, asset);
+        let age = env.ledger().timestamp().saturating_sub(data.timestamp);
+        if age > config.max_age {
+            panic_with_error!(env, Error::OraclePriceStale);
+        }
+        let deviation = mul_div_floor(env, (data.price - one).abs(), BPS_DENOMINATOR, *one);
+        if deviation > config.max_deviation_bps as i128 {
+            panic_with_error!(env, Error::CollateralDepegged);
+        }
+    }
+
+    pub(crate) fn value_of(&self, env: &Env, asset: &Address, amount: i128) -> i128 {
+        let (price, one) = self.backing_price(env, asset);
+        mul_div_floor(env, amount, price, one)
+    }
+
+    pub(crate) fn units_for(&self, env: &Env, asset: &Address, value: i128) -> i128 {
+        let (price, one) = self.backing_price(env, asset);
+        mul_div_floor(env, value, one, price)
+    }
+}
+
+// A non-positive price is treated as missing: it cannot value backing and
+// would divide by zero when converting value back into units.
+fn read_price(env: &Env, client: &PriceFeedClient, asset: &Address) -> PriceData {
+    client
+        .lastprice(&Asset::Stellar(asset.clone()))
+        .filter(|data| data.price > 0)
+        .unwrap_or_else(|| panic_with_error!(env, Error::OraclePriceUnavailable))
+}
@@ -0,0 +1,449 @@
+use soroban_sdk::{contractevent, contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::{
+    accounting, checkpoints, compliance, cost_basis, limits, lock::ReentrancyLock, mul_div_floor,
+    oracle::Pricing, read_config, read_liquidity, read_reserved_usdc, read_shares,
+    require_positive_amount, usdc_client, write_reserved_usdc, write_shares, write_total_shares,
+    Context, DataKey, Error, RUsdToken, RUsdTokenArgs, RUsdTokenClient, SharesBurned,
+    SharesTransferred,
+};
+
+// Requests live in persistent storage, one entry each, so an unbounded queue
+// never grows the instance entry every call loads.
+const DAY_IN_LEDGERS: u32 = 17_280;
+const REQUEST_EXTEND_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const REQUEST_TTL_THRESHOLD: u32 = REQUEST_EXTEND_AMOUNT - DAY_IN_LEDGERS;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RedeemRequest {
+    pub owner: Address,
+    pub shares: i128,
+    pub usdc_owed: i128,
+    pub claimable: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RedeemRequested {
+    #[topic]
+    pub owner: Address,
+    pub request_id: u64,
+    pub shares: i128,
+    pub usdc_owed: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RedeemFilled {
+    #[topic]
+    pub owner: Address,
+    pub request_id: u64,
+    pub shares: i128,
+    pub usdc: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RedeemCancelled {
+    #[topic]
+    pub owner: Address,
+    pub request_id: u64,
+    pub shares: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RedeemClaimed {
+    #[topic]
+    pub owner: Address,
+    pub request_id: u64,
+    pub usdc: i128,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    pub fn request_redeem(env: Env, owner: Address, shares: i128) -> u64 {
+        let _lock = ReentrancyLock::acquire(&env);
+        require_positive_amount(&env, shares);
+        owner.require_auth();
+        compliance::require_can_hold(&env, &owner);
+        let owner_shares = read_shares(&env, &owner);
+        if owner_shares < shares {
+            panic_with_error!(&env, Error::InsufficientShares);
+        }
+        let mut ctx = Context::load(&env);
+        let pricing = Pricing::load(&env);
+        accounting::mark_to_market(&env, &mut ctx, &pricing);
+        let rusd_owed = ctx.rusd_from_shares(&env, shares);
+        let usdc_owed = pricing.units_for(&env, &ctx.config.usdc_contract_id, rusd_owed);
+        write_shares(&env, &owner, checked_sub(&env, owner_shares, shares));
+        cost_basis::remove_principal(&env, &owner, rusd_owed, shares, owner_shares);
+        write_queued_shares(&env, checked_add(&env, read_queued_shares(&env), shares));
+
+        let request_id = read_queue_tail(&env);
+        let request = RedeemRequest {
+            owner: owner.clone(),
+            shares,
+            usdc_owed,
+            claimable: 0,
+        };
+        write_request(&env, request_id, &request);
+        env.storage()
+            .instance()
+            .set(&DataKey::QueueTail, &(request_id + 1));
+
+        SharesTransferred {
+            from: owner.clone(),
+            to: env.current_contract_address(),
+            shares,
+        }
+        .publish(&env);
+        RedeemRequested {
+            owner,
+            request_id,
+            shares,
+            usdc_owed,
+        }
+        .publish(&env);
+        request_id
+    }
+
+    pub fn cancel_redeem(env: Env, request_id: u64) {
+        let _lock = ReentrancyLock::acquire(&env);
+        let mut request = read_request(&env, request_id);
+        request.owner.require_auth();
+        compliance::require_not_frozen(&env, &request.owner);
+        let mut shares = request.shares;
+        if shares > 0 {
+            let mut ctx = Context::load(&env);
+            let pricing = Pricing::load(&env);
+            accounting::mark_to_market(&env, &mut ctx, &pricing);
+            let value = ctx.rusd_from_shares(&env, shares);
+            let owed = pricing.value_of(&env, &ctx.config.usdc_contract_id, request.usdc_owed);
+            // Yield accrued while queued is forfeited: only shares worth the
+            // original claim come back, the rest are burned for the other holders.
+            let returned = if owed < value {
+                shares.min(mul_div_floor(&env, owed, ctx.total_shares, ctx.underlying))
+            } else {
+                shares
+            };
+            let rusd_returned = value.min(owed);
+            let contract = env.current_contract_address();
+            if returned < shares {
+                ctx.total_shares = checked_sub(&env, ctx.total_shares, shares - returned);
+                write_total_shares(&env, ctx.total_shares);
+                checkpoints::record(&env, &ctx);
+                SharesBurned {
+                    from: contract.clone(),
+                    shares: shares - returned,
+                }
+                .publish(&env);
+            }
+            cost_basis::add_principal(&env, &request.owner, rusd_returned);
+            let owner_shares = read_shares(&env, &request.owner);
+            write_shares(
+                &env,
+                &request.owner,
+                checked_add(&env, owner_shares, returned),
+            );
+            write_queued_shares(&env, checked_sub(&env, read_queued_shares(&env), shares));
+            SharesTransferred {
+                from: contract,
+                to: request.owner.clone(),
+                shares: returned,
+            }
+            .publish(&env);
+            shares = returned;
+        }
+        request.shares = 0;
+        request.usdc_owed = 0;
+        if request.claimable == 0 {
+            remove_request(&env, request_id);
+        } else {
+            write_request(&env, request_id, &request);
+        }
+        RedeemCancelled {
+            owner: request.owner,
+            request_id,
+            shares,
+        }
+        .publish(&env);
+    }
+
+    pub fn process_queue(env: Env, max_items: u32) -> u32 {
+        let _lock = ReentrancyLock::acquire(&env);
+        let tail = read_queue_tail(&env);
+        let mut head = read_queue_head(&env);
+        let mut cursor = head;
+        let mut visited = 0;
+        let mut processed = 0;
+        let mut ctx = Context::load(&env);
+        let pricing = Pricing::load(&env);
+        accounting::mark_to_market(&env, &mut ctx, &pricing);
+        let mut liquidity = read_liquidity(&env, &ctx.config);
+        if let Some(capacity) = limits::remaining_capacity(&env, &ctx) {
+            liquidity = liquidity.min(capacity);
+        }
+        // Requests of frozen owners are skipped, not filled; the head stays on
+        // the first one so it is picked up again once the owner is unfrozen.
+        while cursor < tail && visited < max_items {
+            let Some(mut request) = try_read_request(&env, cursor) else {
+                if head == cursor {
+                    head += 1;
+                }
+                cursor += 1;
+                continue;
+            };
+            if request.shares == 0 {
+                if head == cursor {
+                    head += 1;
+                }
+                cursor += 1;
+                continue;
+            }
+            visited += 1;
+            if compliance::is_frozen(&env, &request.owner) {
+                cursor += 1;
+                continue;
+            }
+            if liquidity == 0 {
+                break;
+            }
+            let owed = current_owed(&env, &ctx, &pricing, &request);
+            let (shares_filled, usdc_filled) = if owed <= liquidity {
+                (request.shares, owed)
+            } else {
+                let shares_filled = mul_div_floor(&env, request.shares, liquidity, owed);
+                (shares_filled, liquidity)
+            };
+            if shares_filled == 0 {
+                break;
+            }
+            limits::record_outflow(&env, &ctx, usdc_filled);
+            fill(
+                &env,
+                &mut ctx,
+                &pricing,
+                cursor,
+                &mut request,
+                shares_filled,
+                usdc_filled,
+            );
+            liquidity -= usdc_filled;
+            processed += 1;
+            if request.shares > 0 {
+                break;
+            }
+            if head == cursor {
+                head += 1;
+            }
+            cursor += 1;
+        }
+        env.storage().instance().set(&DataKey::QueueHead, &head);
+        if processed > 0 {
+            accounting::write_total_assets(&env, ctx.underlying);
+            checkpoints::record(&env, &ctx);
+        }
+        processed
+    }
+
+    pub fn claim(env: Env, request_id: u64) -> i128 {
+        let _lock = ReentrancyLock::acquire(&env);
+        let mut request = read_request(&env, request_id);
+        request.owner.require_auth();
+        compliance::require_not_frozen(&env, &request.owner);
+        let usdc_out = request.claimable;
+        if usdc_out == 0 {
+            panic_with_error!(&env, Error::NothingToClaim);
+        }
+        request.claimable = 0;
+        if request.shares == 0 {
+            remove_request(&env, request_id);
+        } else {
+            write_request(&env, request_id, &request);
+        }
+        write_reserved_usdc(&env, checked_sub(&env, read_reserved_usdc(&env), usdc_out));
+        let contract = env.current_contract_address();
+        let usdc = usdc_client(&env, &read_config(&env));
+        usdc.transfer(&contract, &request.owner, &usdc_out);
+        RedeemClaimed {
+            owner: request.owner,
+            request_id,
+            usdc: usdc_out,
+        }
+        .publish(&env);
+        usdc_out
+    }
+
+    pub fn redeem_request(env: Env, request_id: u64) -> Option<RedeemRequest> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RedeemRequest(request_id))
+    }
+
+    pub fn queue_head(env: Env) -> u64 {
+        read_queue_head(&env)
+    }
+
+    pub fn queue_tail(env: Env) -> u64 {
+        read_queue_tail(&env)
+    }
+
+    pub fn queued_shares(env: Env) -> i128 {
+        read_queued_shares(&env)
+    }
+
+    pub fn reserved_usdc(env: Env) -> i128 {
+        read_reserved_usdc(&env)
+    }
+}
+
+// Removes a request for clawback: escrowed shares are burned at the value the
+// queue would pay for them, and claimable USDC is released from the reserve.
+// Returns the owner, the rUSD value burned and the USDC to send out.
+pub(crate) fn seize(env: &Env, ctx: &mut Context, request_id: u64) -> (Address, i128, i128) {
+    let request = read_request(env, request_id);
+    let mut rusd_out = 0;
+    let mut usdc_out = request.claimable;
+    if request.shares > 0 {
+        let pricing = Pricing::load(env);
+        accounting::mark_to_market(env, ctx, &pricing);
+        let owed = current_owed(env, ctx, &pricing, &request);
+        rusd_out = pricing.value_of(env, &ctx.config.usdc_contract_id, owed);
+        ctx.total_shares = checked_sub(env, ctx.total_shares, request.shares);
+        ctx.release(env, rusd_out);
+        write_total_shares(env, ctx.total_shares);
+        write_queued_shares(
+            env,
+            checked_sub(env, read_queued_shares(env), request.shares),
+        );
+        usdc_out = checked_add(env, usdc_out, owed);
+        SharesBurned {
+            from: env.current_contract_address(),
+            shares: request.shares,
+        }
+        .publish(env);
+    }
+    write_reserved_usdc(
+        env,
+        checked_sub(env, read_reserved_usdc(env), request.claimable),
+    );
+    remove_request(env, request_id);
+    (request.owner, rusd_out, usdc_out)
+}
+
+// Queued shares keep absorbing losses but not gains: a request is worth the
+// lower of what was owed when it was made and what its shares are worth now.
+fn current_owed(env: &Env, ctx: &Context, pricing: &Pricing, request: &RedeemRequest) -> i128 {
+    let value = ctx.rusd_from_shares(env, request.shares);
+    let usdc_value = pricing.units_for(env, &ctx.config.usdc_contract_id, value);
+    request.usdc_owed.min(usdc_value)
+}
+
+fn fill(
+    env: &Env,
+    ctx: &mut Context,
+    pricing: &Pricing,
+    request_id: u64,
+    request: &mut RedeemRequest,
+    shares: i128,
+    usdc: i128,
+) {
+    let owed_released = if shares == request.shares {
+        request.usdc_owed
+    } else {
+        mul_div_floor(env, request.usdc_owed, shares, request.shares)
+    };
+    ctx.total_shares = checked_sub(env, ctx.total_shares, shares);
+    ctx.release(env, pricing.value_of(env, &ctx.config.usdc_contract_id, usdc));
+    write_total_shares(env, ctx.total_shares);
+    write_queued_shares(env, checked_sub(env, read_queued_shares(env), shares));
+    write_reserved_usdc(env, checked_add(env, read_reserved_usdc(env), usdc));
+    request.shares -= shares;
+    request.usdc_owed -= owed_released;
+    request.claimable = checked_add(env, request.claimable, usdc);
+    write_request(env, request_id, request);
+    SharesBurned {
+        from: env.current_contract_address(),
+        shares,
+    }
+    .publish(env);
+    RedeemFilled {
+        owner: request.owner.clone(),
+        request_id,
+        shares,
+        usdc,
+    }
+    .publish(env);
+}
+
+fn checked_add(env: &Env, a: i128, b: i128) -> i128 {
+    a.checked_add(b)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+}
+
+fn checked_sub(env: &Env, a: i128, b: i128) -> i128 {
+    a.checked_sub(b)
+        .filter(|difference| *difference >= 0)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+}
+
+fn read_queue_head(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::QueueHead)
+        .unwrap_or(0)
+}
+
+fn read_queue_tail(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::QueueTail)
+        .unwrap_or(0)
+}
+
+fn read_queued_shares(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::QueuedShares)
+        .unwrap_or(0)
+}
+
+fn write_queued_shares(env: &Env, shares: i128) {
+    env.storage()
+        .instance()
+        .set(&DataKey::QueuedShares, &shares);
+}
+
+fn try_read_request(env: &Env, request_id: u64) -> Option<RedeemRequest> {
+    let key = DataKey::RedeemRequest(request_id);
+    let request = env.storage().persistent().get(&key);
+    if request.is_some() {
+        extend_request(env, &key);
+    }
+    request
+}
+
+fn read_request(env: &Env, request_id: u64) -> RedeemRequest {
+    try_read_request(env, request_id)
+        .unwrap_or_else(|| panic_with_error!(env, Error::RequestNotFound))
+}
+
+fn write_request(env: &Env, request_id: u64, request: &RedeemRequest) {
+    let key = DataKey::RedeemRequest(request_id);
+    env.storage().persistent().set(&key, request);
+    extend_request(env, &key);
+}
+
+fn remove_request(env: &Env, request_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::RedeemRequest(request_id));
+}
+
+fn extend_request(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, REQUEST_TTL_THRESHOLD, REQUEST_EXTEND_AMOUNT);
+}
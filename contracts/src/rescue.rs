@@ -0,0 +1,36 @@
+use soroban_sdk::{
+    contractevent, contractimpl, panic_with_error, token::Client as TokenClient, Address, Env,
+};
+use stellar_macros::only_admin;
+
+use crate::{
+    collateral, lock::ReentrancyLock, require_positive_amount, strategy, Error, RUsdToken,
+    RUsdTokenArgs, RUsdTokenClient,
+};
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenRescued {
+    #[topic]
+    pub token: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    #[only_admin]
+    pub fn rescue_token(env: Env, token: Address, to: Address, amount: i128) {
+        let _lock = ReentrancyLock::acquire(&env);
+        require_positive_amount(&env, amount);
+        if collateral::is_registered(&env, &token)
+            || strategy::read_yield_vault(&env) == Some(token.clone())
+        {
+            panic_with_error!(&env, Error::ProtectedToken);
+        }
+        let contract = env.current_contract_address();
+        TokenClient::new(&env, &token).transfer(&contract, &to, &amount);
+        TokenRescued { token, to, amount }.publish(&env);
+    }
+}
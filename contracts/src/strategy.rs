@@ -0,0 +1,176 @@
+use rusd_math::BPS_DENOMINATOR;
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contractclient, contractevent, contractimpl, panic_with_error, vec, Address, Env, IntoVal,
+    Symbol,
+};
+use stellar_macros::only_admin;
+
+use crate::{
+    bps_of, lock::ReentrancyLock, mul_div_floor, read_config, read_liquidity, timelock, Config,
+    Context, DataKey, Error, RUsdToken, RUsdTokenArgs, RUsdTokenClient,
+};
+
+#[contractclient(name = "YieldVaultClient")]
+pub trait YieldVault {
+    fn deposit(env: Env, from: Address, amount: i128);
+    fn withdraw(env: Env, to: Address, amount: i128);
+    fn balance(env: Env, id: Address) -> i128;
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deploy {
+    #[topic]
+    pub vault: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Recall {
+    #[topic]
+    pub vault: Address,
+    pub amount: i128,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    #[only_admin]
+    pub fn set_yield_vault(env: Env, vault: Option<Address>) {
+        let _lock = ReentrancyLock::acquire(&env);
+        timelock::require_unlocked(&env);
+        apply_yield_vault(&env, vault);
+    }
+
+    pub fn yield_vault(env: Env) -> Option<Address> {
+        read_yield_vault(&env)
+    }
+
+    #[only_admin]
+    pub fn set_buffer_target(env: Env, bps: u32) {
+        let _lock = ReentrancyLock::acquire(&env);
+        timelock::require_unlocked(&env);
+        apply_buffer_target(&env, bps);
+    }
+
+    pub fn buffer_target(env: Env) -> u32 {
+        read_buffer_target(&env)
+    }
+
+    pub fn buffer_actual(env: Env) -> u32 {
+        let ctx = Context::load(&env);
+        if ctx.underlying == 0 {
+            return 0;
+        }
+        let idle = read_liquidity(&env, &ctx.config);
+        mul_div_floor(&env, idle, BPS_DENOMINATOR, ctx.underlying) as u32
+    }
+
+    pub fn idle_usdc(env: Env) -> i128 {
+        read_liquidity(&env, &read_config(&env))
+    }
+
+    pub fn deployed_usdc(env: Env) -> i128 {
+        read_deployed(&env)
+    }
+
+    #[only_admin]
+    pub fn recall(env: Env, amount: i128) {
+        let _lock = ReentrancyLock::acquire(&env);
+        let vault = read_yield_vault(&env)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::YieldVaultNotSet));
+        withdraw(&env, &vault, amount);
+    }
+}
+
+pub(crate) fn apply_yield_vault(env: &Env, vault: Option<Address>) {
+    if read_deployed(env) != 0 {
+        panic_with_error!(env, Error::YieldVaultNotEmpty);
+    }
+    match vault {
+        Some(vault) => env.storage().instance().set(&DataKey::YieldVault, &vault),
+        None => env.storage().instance().remove(&DataKey::YieldVault),
+    }
+}
+
+pub(crate) fn apply_buffer_target(env: &Env, bps: u32) {
+    if bps as i128 > BPS_DENOMINATOR {
+        panic_with_error!(env, Error::InvalidBufferTarget);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::BufferTargetBps, &bps);
+}
+
+pub(crate) fn read_yield_vault(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::YieldVault)
+}
+
+fn read_buffer_target(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::BufferTargetBps)
+        .unwrap_or(BPS_DENOMINATOR as u32)
+}
+
+pub(crate) fn read_deployed(env: &Env) -> i128 {
+    match read_yield_vault(env) {
+        Some(vault) => YieldVaultClient::new(env, &vault).balance(&env.current_contract_address()),
+        None => 0,
+    }
+}
+
+pub(crate) fn deploy_excess(env: &Env, ctx: &Context) {
+    let Some(vault) = read_yield_vault(env) else {
+        return;
+    };
+    let target_idle = bps_of(env, ctx.underlying, read_buffer_target(env));
+    let excess = read_liquidity(env, &ctx.config) - target_idle;
+    if excess <= 0 {
+        return;
+    }
+    let contract = env.current_contract_address();
+    let usdc_contract_id = ctx.config.usdc_contract_id.clone();
+    env.authorize_as_current_contract(vec![
+        env,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: usdc_contract_id,
+                fn_name: Symbol::new(env, "transfer"),
+                args: (contract.clone(), vault.clone(), excess).into_val(env),
+            },
+            sub_invocations: vec![env],
+        }),
+    ]);
+    YieldVaultClient::new(env, &vault).deposit(&contract, &excess);
+    Deploy {
+        vault,
+        amount: excess,
+    }
+    .publish(env);
+}
+
+pub(crate) fn ensure_liquidity(env: &Env, config: &Config, usdc_out: i128) -> i128 {
+    let liquidity = read_liquidity(env, config);
+    let shortfall = usdc_out - liquidity;
+    if shortfall <= 0 {
+        return liquidity;
+    }
+    match read_yield_vault(env) {
+        Some(vault) => {
+            withdraw(env, &vault, shortfall);
+            read_liquidity(env, config)
+        }
+        None => liquidity,
+    }
+}
+
+fn withdraw(env: &Env, vault: &Address, amount: i128) {
+    YieldVaultClient::new(env, vault).withdraw(&env.current_contract_address(), &amount);
+    Recall {
+        vault: vault.clone(),
+        amount,
+    }
+    .publish(env);
+}
@@ -0,0 +1,369 @@
+#![cfg(any(test, feature = "testutils"))]
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Ledger as _},
+    Address, Env, String,
+};
+
+use crate::{RUsdToken, RUsdTokenClient};
+
+pub const LEDGER_SECONDS: u64 = 5;
+
+#[derive(Clone)]
+#[contracttype]
+struct AllowanceValue {
+    amount: i128,
+    expiration_ledger: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ReentryCall {
+    Sync,
+    Mint(Address, i128),
+    Burn(Address, i128),
+    Transfer(Address, Address, i128),
+    TransferFrom(Address, Address, Address, i128),
+}
+
+#[derive(Clone)]
+#[contracttype]
+enum UsdcKey {
+    Decimals,
+    FeeBps,
+    Paused,
+    SkipTransfers,
+    Reentry,
+    ReentryCall,
+    Blocked(Address),
+    Balance(Address),
+    Allowance(Address, Address),
+}
+
+#[contract]
+pub struct UsdcMock;
+
+#[contractimpl]
+impl UsdcMock {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = read_balance(&env, &to);
+        write_balance(&env, &to, balance + amount);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        read_balance(&env, &id)
+    }
+
+    pub fn set_decimals(env: Env, decimals: u32) {
+        env.storage().instance().set(&UsdcKey::Decimals, &decimals);
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&UsdcKey::Decimals)
+            .unwrap_or(7)
+    }
+
+    pub fn set_fee_bps(env: Env, fee_bps: u32) {
+        env.storage().instance().set(&UsdcKey::FeeBps, &fee_bps);
+    }
+
+    pub fn set_paused(env: Env, paused: bool) {
+        env.storage().instance().set(&UsdcKey::Paused, &paused);
+    }
+
+    pub fn set_skip_transfers(env: Env, skip: bool) {
+        env.storage().instance().set(&UsdcKey::SkipTransfers, &skip);
+    }
+
+    pub fn set_reentry(env: Env, target: Option<Address>) {
+        match target {
+            Some(target) => env.storage().instance().set(&UsdcKey::Reentry, &target),
+            None => env.storage().instance().remove(&UsdcKey::Reentry),
+        }
+    }
+
+    pub fn set_reentry_call(env: Env, call: ReentryCall) {
+        env.storage().instance().set(&UsdcKey::ReentryCall, &call);
+    }
+
+    pub fn set_blocked(env: Env, account: Address, blocked: bool) {
+        env.storage()
+            .instance()
+            .set(&UsdcKey::Blocked(account), &blocked);
+    }
+
+    pub fn approve(
+        env: Env,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) {
+        from.require_auth();
+        write_allowance(&env, &from, &spender, amount, expiration_ledger);
+    }
+
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        read_allowance_amount(&env, &from, &spender)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        from.require_auth();
+        move_balance(&env, &from, &to, amount);
+    }
+
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        spender.require_auth();
+        let allowance = read_allowance_amount(&env, &from, &spender);
+        if allowance < amount {
+            panic!("insufficient allowance");
+        }
+        move_balance(&env, &from, &to, amount);
+        write_allowance(
+            &env,
+            &from,
+            &spender,
+            allowance - amount,
+            read_allowance_expiration(&env, &from, &spender),
+        );
+    }
+}
+
+fn move_balance(env: &Env, from: &Address, to: &Address, amount: i128) {
+    let storage = env.storage().instance();
+    if storage.get(&UsdcKey::Paused).unwrap_or(false) {
+        panic!("token paused");
+    }
+    if storage
+        .get(&UsdcKey::Blocked(from.clone()))
+        .unwrap_or(false)
+        || storage.get(&UsdcKey::Blocked(to.clone())).unwrap_or(false)
+    {
+        panic!("account blocked");
+    }
+    if let Some(target) = storage.get::<_, Address>(&UsdcKey::Reentry) {
+        let rusd = RUsdTokenClient::new(env, &target);
+        match storage
+            .get(&UsdcKey::ReentryCall)
+            .unwrap_or(ReentryCall::Sync)
+        {
+            ReentryCall::Sync => rusd.sync(),
+            ReentryCall::Mint(to, amount) => rusd.mint(&to, &amount),
+            ReentryCall::Burn(from, amount) => rusd.burn(&from, &amount),
+            ReentryCall::Transfer(from, to, amount) => rusd.transfer(&from, &to, &amount),
+            ReentryCall::TransferFrom(spender, from, to, amount) => {
+                rusd.transfer_from(&spender, &from, &to, &amount)
+            }
+        }
+    }
+    if storage.get(&UsdcKey::SkipTransfers).unwrap_or(false) {
+        return;
+    }
+    let fee_bps: u32 = storage.get(&UsdcKey::FeeBps).unwrap_or(0);
+    let fee = rusd_math::bps_of(amount, fee_bps).unwrap();
+    spend_balance(env, from, amount);
+    add_balance(env, to, amount - fee);
+}
+
+fn read_balance(env: &Env, owner: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&UsdcKey::Balance(owner.clone()))
+        .unwrap_or(0)
+}
+
+fn write_balance(env: &Env, owner: &Address, amount: i128) {
+    env.storage()
+        .instance()
+        .set(&UsdcKey::Balance(owner.clone()), &amount);
+}
+
+fn add_balance(env: &Env, owner: &Address, amount: i128) {
+    let balance = read_balance(env, owner);
+    write_balance(env, owner, balance + amount);
+}
+
+fn spend_balance(env: &Env, owner: &Address, amount: i128) {
+    let balance = read_balance(env, owner);
+    if balance < amount {
+        panic!("insufficient balance");
+    }
+    write_balance(env, owner, balance - amount);
+}
+
+fn read_allowance(env: &Env, owner: &Address, spender: &Address) -> AllowanceValue {
+    env.storage()
+        .instance()
+        .get(&UsdcKey::Allowance(owner.clone(), spender.clone()))
+        .unwrap_or(AllowanceValue {
+            amount: 0,
+            expiration_ledger: 0,
+        })
+}
+
+fn read_allowance_amount(env: &Env, owner: &Address, spender: &Address) -> i128 {
+    let allowance = read_allowance(env, owner, spender);
+    let current = env.ledger().sequence();
+    if allowance.expiration_ledger != 0 && allowance.expiration_ledger < current {
+        0
+    } else {
+        allowance.amount
+    }
+}
+
+fn read_allowance_expiration(env: &Env, owner: &Address, spender: &Address) -> u32 {
+    read_allowance(env, owner, spender).expiration_ledger
+}
+
+fn write_allowance(
+    env: &Env,
+    owner: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    let key = UsdcKey::Allowance(owner.clone(), spender.clone());
+    if amount == 0 {
+        env.storage().instance().remove(&key);
+    } else {
+        env.storage().instance().set(
+            &key,
+            &AllowanceValue {
+                amount,
+                expiration_ledger,
+            },
+        );
+    }
+}
+
+pub struct Actors {
+    pub admin: Address,
+    pub alice: Address,
+    pub bob: Address,
+    pub carol: Address,
+    pub donor: Address,
+    pub spender: Address,
+}
+
+impl Actors {
+    pub fn generate(env: &Env) -> Self {
+        Actors {
+            admin: Address::generate(env),
+            alice: Address::generate(env),
+            bob: Address::generate(env),
+            carol: Address::generate(env),
+            donor: Address::generate(env),
+            spender: Address::generate(env),
+        }
+    }
+}
+
+pub struct ScenarioBuilder {
+    decimals: u32,
+    fee_bps: u32,
+    name: &'static str,
+    symbol: &'static str,
+}
+
+impl Default for ScenarioBuilder {
+    fn default() -> Self {
+        ScenarioBuilder {
+            decimals: 7,
+            fee_bps: 0,
+            name: "rUSD",
+            symbol: "rUSD",
+        }
+    }
+}
+
+impl ScenarioBuilder {
+    pub fn decimals(mut self, decimals: u32) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    pub fn fee_bps(mut self, fee_bps: u32) -> Self {
+        self.fee_bps = fee_bps;
+        self
+    }
+
+    pub fn metadata(mut self, name: &'static str, symbol: &'static str) -> Self {
+        self.name = name;
+        self.symbol = symbol;
+        self
+    }
+
+    pub fn build(self) -> Scenario {
+        let env = Env::default();
+        env.mock_all_auths();
+        let usdc = UsdcMockClient::new(&env, &env.register(UsdcMock, ()));
+        usdc.set_decimals(&self.decimals);
+        usdc.set_fee_bps(&self.fee_bps);
+        let actors = Actors::generate(&env);
+        let rusd_id = env.register(
+            RUsdToken,
+            (
+                actors.admin.clone(),
+                usdc.address.clone(),
+                String::from_str(&env, self.name),
+                String::from_str(&env, self.symbol),
+                self.decimals,
+            ),
+        );
+        let rusd = RUsdTokenClient::new(&env, &rusd_id);
+        Scenario {
+            env,
+            usdc,
+            rusd,
+            actors,
+        }
+    }
+}
+
+pub struct Scenario {
+    pub env: Env,
+    pub usdc: UsdcMockClient<'static>,
+    pub rusd: RUsdTokenClient<'static>,
+    pub actors: Actors,
+}
+
+impl Scenario {
+    pub fn builder() -> ScenarioBuilder {
+        ScenarioBuilder::default()
+    }
+
+    pub fn fund(&self, to: &Address, amount: i128) {
+        self.usdc.mint(to, &amount);
+    }
+
+    pub fn deposit(&self, to: &Address, amount: i128) {
+        self.fund(to, amount);
+        let live_until = self.env.ledger().sequence() + 1_000;
+        self.usdc
+            .approve(to, &self.rusd.address, &amount, &live_until);
+        self.rusd.mint(to, &amount);
+    }
+
+    pub fn simulate_yield(&self, amount: i128) {
+        self.fund(&self.actors.donor, amount);
+        self.usdc
+            .transfer(&self.actors.donor, &self.rusd.address, &amount);
+        self.rusd.sync();
+    }
+
+    pub fn advance_ledgers(&self, ledgers: u32) {
+        self.env.ledger().with_mut(|li| {
+            li.sequence_number += ledgers;
+            li.timestamp += ledgers as u64 * LEDGER_SECONDS;
+        });
+    }
+}
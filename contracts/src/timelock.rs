@@ -0,0 +1,194 @@
+use soroban_sdk::{contractevent, contractimpl, contracttype, panic_with_error, Address, Env, Vec};
+use stellar_macros::{only_admin, only_role};
+
+use crate::{
+    accounting, collateral, flash,
+    limits::{self, WithdrawalLimit},
+    lock::ReentrancyLock,
+    oracle::{self, OracleConfig},
+    strategy, DataKey, Error, RUsdToken, RUsdTokenArgs, RUsdTokenClient,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TimelockConfig {
+    pub delay: u32,
+    pub grace: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Operation {
+    SetTimelock(TimelockConfig),
+    SetYieldVault(Option<Address>),
+    SetBufferTarget(u32),
+    SetWithdrawalLimit(WithdrawalLimit),
+    ClearWithdrawalLimit,
+    SetCollateral(Address, bool, u32),
+    SetOracle(OracleConfig),
+    ClearOracle,
+    SetDripPeriod(u64),
+    SetFlashFeeBps(u32),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PendingOperation {
+    pub operation: Operation,
+    pub eta: u32,
+    pub expires: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperationScheduled {
+    #[topic]
+    pub id: u64,
+    pub operation: Operation,
+    pub eta: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperationExecuted {
+    #[topic]
+    pub id: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperationCancelled {
+    #[topic]
+    pub id: u64,
+}
+
+#[contractimpl]
+impl RUsdToken {
+    #[only_admin]
+    pub fn set_timelock(env: Env, config: TimelockConfig) {
+        let _lock = ReentrancyLock::acquire(&env);
+        require_unlocked(&env);
+        apply_timelock(&env, config);
+    }
+
+    pub fn timelock(env: Env) -> Option<TimelockConfig> {
+        read_timelock(&env)
+    }
+
+    #[only_admin]
+    pub fn schedule(env: Env, operation: Operation) -> u64 {
+        let _lock = ReentrancyLock::acquire(&env);
+        let config =
+            read_timelock(&env).unwrap_or_else(|| panic_with_error!(&env, Error::TimelockNotSet));
+        let id = read_next_id(&env);
+        let eta = env.ledger().sequence() + config.delay;
+        let pending = PendingOperation {
+            operation: operation.clone(),
+            eta,
+            expires: eta + config.grace,
+        };
+        let key = DataKey::TimelockOperation(id);
+        env.storage().persistent().set(&key, &pending);
+        let live_for = config.delay + config.grace;
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, live_for, live_for);
+        env.storage()
+            .instance()
+            .set(&DataKey::TimelockNextId, &(id + 1));
+        OperationScheduled { id, operation, eta }.publish(&env);
+        id
+    }
+
+    pub fn execute(env: Env, id: u64) {
+        let _lock = ReentrancyLock::acquire(&env);
+        let pending = read_pending(&env, id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::OperationNotFound));
+        let now = env.ledger().sequence();
+        if now < pending.eta {
+            panic_with_error!(&env, Error::OperationNotReady);
+        }
+        if now > pending.expires {
+            panic_with_error!(&env, Error::OperationExpired);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::TimelockOperation(id));
+        match pending.operation {
+            Operation::SetTimelock(config) => apply_timelock(&env, config),
+            Operation::SetYieldVault(vault) => strategy::apply_yield_vault(&env, vault),
+            Operation::SetBufferTarget(bps) => strategy::apply_buffer_target(&env, bps),
+            Operation::SetWithdrawalLimit(limit) => {
+                limits::apply_withdrawal_limit(&env, Some(limit))
+            }
+            Operation::ClearWithdrawalLimit => limits::apply_withdrawal_limit(&env, None),
+            Operation::SetCollateral(asset, enabled, max_share_bps) => {
+                collateral::apply_collateral(&env, asset, enabled, max_share_bps)
+            }
+            Operation::SetOracle(config) => oracle::apply_oracle(&env, Some(config)),
+            Operation::ClearOracle => oracle::apply_oracle(&env, None),
+            Operation::SetDripPeriod(seconds) => accounting::apply_drip_period(&env, seconds),
+            Operation::SetFlashFeeBps(bps) => flash::apply_flash_fee_bps(&env, bps),
+        }
+        OperationExecuted { id }.publish(&env);
+    }
+
+    #[only_role(guardian, "guardian")]
+    pub fn cancel(env: Env, guardian: Address, id: u64) {
+        let _lock = ReentrancyLock::acquire(&env);
+        if read_pending(&env, id).is_none() {
+            panic_with_error!(&env, Error::OperationNotFound);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::TimelockOperation(id));
+        OperationCancelled { id }.publish(&env);
+    }
+
+    pub fn pending_operation(env: Env, id: u64) -> Option<PendingOperation> {
+        read_pending(&env, id)
+    }
+
+    pub fn pending_operations(env: Env, from: u64, limit: u32) -> Vec<(u64, PendingOperation)> {
+        let next_id = read_next_id(&env);
+        let mut result = Vec::new(&env);
+        let mut id = from;
+        while id < next_id && result.len() < limit {
+            if let Some(pending) = read_pending(&env, id) {
+                result.push_back((id, pending));
+            }
+            id += 1;
+        }
+        result
+    }
+}
+
+fn read_timelock(env: &Env) -> Option<TimelockConfig> {
+    env.storage().instance().get(&DataKey::Timelock)
+}
+
+fn read_next_id(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::TimelockNextId)
+        .unwrap_or(0)
+}
+
+fn read_pending(env: &Env, id: u64) -> Option<PendingOperation> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TimelockOperation(id))
+}
+
+fn apply_timelock(env: &Env, config: TimelockConfig) {
+    if config.delay == 0 || config.grace == 0 {
+        panic_with_error!(env, Error::InvalidTimelockConfig);
+    }
+    env.storage().instance().set(&DataKey::Timelock, &config);
+}
+
+pub(crate) fn require_unlocked(env: &Env) {
+    if read_timelock(env).is_some() {
+        panic_with_error!(env, Error::TimelockActive);
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// Builds the token wasm from the workspace sources, so crates that embed it
+// can never pick up a missing or stale file. It uses its own target directory
+// because the outer cargo holds the lock on the shared one.
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let workspace = manifest_dir.parent().unwrap();
+    for path in ["Cargo.toml", "Cargo.lock", "src", "math"] {
+        println!("cargo:rerun-if-changed={}", workspace.join(path).display());
+    }
+
+    // OUT_DIR is <target>/<profile>/build/<package>-<hash>/out.
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let target_dir = out_dir.ancestors().nth(4).unwrap().join("token-wasm");
+    let output = Command::new(env::var("CARGO").unwrap())
+        .args(["build", "--release", "--target", "wasm32v1-none"])
+        .args(["-p", "rusd-rebasing-token", "--manifest-path"])
+        .arg(workspace.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .env_remove("CARGO_ENCODED_RUSTFLAGS")
+        .env_remove("RUSTC_WRAPPER")
+        .env_remove("RUSTC_WORKSPACE_WRAPPER")
+        .output()
+        .expect("cannot run cargo");
+    if !output.status.success() {
+        panic!(
+            "building the token wasm failed (is the wasm32v1-none target installed?):\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let wasm = target_dir.join("wasm32v1-none/release/rusd_rebasing_token.wasm");
+    assert!(
+        Path::new(&wasm).is_file(),
+        "{} was not built",
+        wasm.display()
+    );
+    println!("cargo:rustc-env=RUSD_TOKEN_WASM={}", wasm.display());
+}
@@ -0,0 +1,6 @@
+//! The `RUsdToken` wasm, rebuilt from the workspace sources whenever they
+//! change.
+
+pub const PATH: &str = env!("RUSD_TOKEN_WASM");
+
+pub const WASM: &[u8] = include_bytes!(env!("RUSD_TOKEN_WASM"));
@@ -1,6 +1,8 @@
 #!/usr/bin/env bash
 set -euo pipefail
 
+# Superseded by `rusd-cli` (contracts/cli), which reads its settings from rusd.toml.
+
 ROOT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
 CONTRACTS_DIR="${ROOT_DIR}/contracts"
 ENV_FILE="${ROOT_DIR}/.env"
@@ -58,26 +60,24 @@ if [[ ! -f "${WASM_PATH}" ]]; then
   exit 1
 fi
 
+ADMIN_ADDRESS="${RUSD_ADMIN:-$(${CLI_BIN} keys address "${IDENTITY}")}"
+
+# The constructor runs in the deployment transaction and requires the admin's
+# authorization, so RUSD_ADMIN must be the signing identity.
 echo "Deploying contract..."
 RUSD_CONTRACT_ID="$(
   ${CLI_BIN} contract deploy \
     --wasm "${WASM_PATH}" \
     ${SOURCE_FLAG} "${IDENTITY}" \
-    --network "${NETWORK}"
+    --network "${NETWORK}" \
+    -- \
+    --admin "${ADMIN_ADDRESS}" \
+    --usdc_contract_id "${USDC_CONTRACT_ID}" \
+    --name "rUSD" \
+    --symbol "rUSD" \
+    --decimals 7
 )"
 
-echo "Initializing contract..."
-${CLI_BIN} contract invoke \
-  --id "${RUSD_CONTRACT_ID}" \
-  ${SOURCE_FLAG} "${IDENTITY}" \
-  --network "${NETWORK}" \
-  -- \
-  init \
-  --usdc_contract_id "${USDC_CONTRACT_ID}" \
-  --name "rUSD" \
-  --symbol "rUSD" \
-  --decimals 7
-
 update_env() {
   local key="$1"
   local value="$2"